@@ -0,0 +1,74 @@
+//! JSON messages printed by `cargo criterion --message-format json`, read by
+//! the runner while benchmarking and by the plot when ingesting such output.
+
+use serde::{Deserialize, Serialize};
+
+pub type Time = f64;
+
+/// Point estimate with the bounds of its confidence interval.
+#[derive(Clone, Copy, Deserialize, Serialize)]
+pub struct Estimate {
+    pub estimate: Time,
+    pub lower_bound: Time,
+    pub upper_bound: Time,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "reason")]
+pub enum Message {
+    BenchmarkComplete {
+        id: String,
+        iteration_count: Vec<u64>,
+        mean: Estimate,
+        measured_values: Vec<Time>,
+        median: Estimate,
+
+        #[serde(default)]
+        throughput: Vec<Throughput>,
+    },
+
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+pub struct Throughput {
+    pub per_iteration: u64,
+    pub unit: String,
+}
+
+/// Sample standard deviation of the time per iteration, with each sample
+/// measuring the total time of its iterations.
+pub fn std_dev(measured_values: &[Time], iteration_count: &[u64]) -> Time {
+    let samples = measured_values
+        .iter()
+        .zip(iteration_count)
+        .map(|(time, count)| time / *count as Time)
+        .collect::<Vec<_>>();
+
+    let count = samples.len() as Time;
+    let mean = samples.iter().sum::<Time>() / count;
+
+    // A single sample has no spread, rather than an undefined one.
+    (samples
+        .iter()
+        .map(|time| (time - mean).powi(2))
+        .sum::<Time>()
+        / (count - 1.0).max(1.0))
+    .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn std_dev_of_single_sample_is_zero() {
+        assert_eq!(std_dev(&[4.0], &[2]), 0.0);
+    }
+
+    #[test]
+    fn std_dev_of_time_per_iteration() {
+        assert_eq!(std_dev(&[2.0, 8.0, 6.0], &[1, 2, 1]), 2.0);
+    }
+}
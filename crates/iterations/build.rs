@@ -9,6 +9,8 @@ use std::{
 };
 
 const BENCH: &str = "bench";
+const FUZZ_MANIFEST: &str = "fuzz/Cargo.toml";
+const FUZZ_TARGETS_DIR: &str = "fuzz/fuzz_targets";
 const INPUT_DIR: &str = "data";
 const ITERATIONS: &str = "iterations";
 const OUT_DIR: &str = "OUT_DIR";
@@ -35,6 +37,88 @@ fn generate_bench(
 ) -> io::Result<()> {
     const GROUP: &str = "benches";
 
+    writeln_indented!(
+        file,
+        0,
+        "static INPUTS: std::sync::LazyLock<Vec<iterations::input::Input>> = std::sync::LazyLock::new(|| {{"
+    )?;
+
+    writeln_indented!(
+        file,
+        1,
+        "let inputs = std::env::var_os(\"INPUT\").unwrap_or_else(|| panic!(\"INPUT pointing to the input files or directories is not set\"));"
+    )?;
+
+    writeln_indented!(
+        file,
+        1,
+        "iterations::input::Input::from_paths(std::env::split_paths(&inputs)).expect(\"inputs should be readable\")"
+    )?;
+
+    writeln_indented!(file, 0, "}});\n")?;
+
+    writeln_indented!(
+        file,
+        0,
+        "fn throughput(input: &iterations::input::Input) -> criterion::Throughput {{"
+    )?;
+
+    writeln_indented!(
+        file,
+        1,
+        "match std::env::var(\"THROUGHPUT\").as_deref() {{"
+    )?;
+
+    writeln_indented!(
+        file,
+        2,
+        "Ok(\"bytes\") => criterion::Throughput::Bytes(input.bytes),"
+    )?;
+
+    writeln_indented!(
+        file,
+        2,
+        "Ok(\"elements\") | Err(std::env::VarError::NotPresent) => criterion::Throughput::Elements(input.rows),"
+    )?;
+
+    writeln_indented!(
+        file,
+        2,
+        "throughput => panic!(\"THROUGHPUT should be either 'bytes' or 'elements': {{throughput:?}}\"),"
+    )?;
+
+    writeln_indented!(file, 1, "}}")?;
+    writeln_indented!(file, 0, "}}\n")?;
+
+    writeln_indented!(
+        file,
+        0,
+        "fn measure(b: &mut criterion::Bencher, input: &std::path::Path, iteration: fn(&std::path::Path) -> String) {{"
+    )?;
+
+    writeln_indented!(file, 1, "match std::env::var(\"CACHE\").as_deref() {{")?;
+
+    writeln_indented!(
+        file,
+        2,
+        "Ok(\"cold\") => b.iter_batched(|| iterations::input::drop_page_cache(input).expect(\"page cache should be droppable\"), |()| iteration(input), criterion::BatchSize::PerIteration),"
+    )?;
+
+    writeln_indented!(
+        file,
+        2,
+        "Ok(\"warm\") | Err(std::env::VarError::NotPresent) => b.iter(|| iteration(input)),"
+    )?;
+
+    writeln_indented!(
+        file,
+        2,
+        "cache => panic!(\"CACHE should be either 'cold' or 'warm': {{cache:?}}\"),"
+    )?;
+
+    writeln_indented!(file, 1, "}}")?;
+    writeln_indented!(file, 0, "}}\n")?;
+
     for iteration in iterations {
         writeln_indented!(
             file,
@@ -46,22 +130,22 @@ fn generate_bench(
         writeln_indented!(
             file,
             1,
-            "let input = &std::env::var(\"INPUT\").unwrap_or_else(|_| panic!(\"INPUT pointing to the input file is not set\"));"
+            "let mut group = c.benchmark_group(\"{0}\");\n",
+            iteration
         )?;
 
-        writeln_indented!(
-            file,
-            1,
-            "let input = &std::path::Path::new(input);"
-        )?;
+        writeln_indented!(file, 1, "for input in INPUTS.iter() {{")?;
+        writeln_indented!(file, 2, "group.throughput(throughput(input));")?;
 
         writeln_indented!(
             file,
-            1,
-            "c.bench_function(\"{0}\", |b| b.iter(|| iterations::{0}(input)));",
+            2,
+            "group.bench_with_input(criterion::BenchmarkId::new(input.name(), input.rows), &input.path, |b, input| measure(b, input, iterations::{0}));",
             iteration
         )?;
 
+        writeln_indented!(file, 1, "}}\n")?;
+        writeln_indented!(file, 1, "group.finish();")?;
         writeln_indented!(file, 0, "}}\n")?;
     }
 
@@ -81,6 +165,9 @@ fn generate_bench(
     Ok(())
 }
 
+/// Generate a `cargo-fuzz` target per iteration with its `[[bin]]` entry in the
+/// manifest of the fuzzing package, and seed the corpus of each target with the
+/// test inputs.
 fn generate_iterations(
     iterations: &Vec<String>,
     mut file: impl Write,
@@ -137,6 +224,15 @@ fn generate_iterations(
     writeln_indented!(file, 1, "}}")?;
     writeln_indented!(file, 0, "}}\n")?;
 
+    writeln_indented!(
+        file,
+        0,
+        "pub const BASE: Iteration = Iteration::{};\n",
+        iterations
+            .first()
+            .expect("base implementation should exist"),
+    )?;
+
     writeln_indented!(
         file,
         0,
@@ -227,6 +323,70 @@ fn generate_iterations(
         writeln_indented!(file, 1, "}}")?;
     }
 
+    // The fuzz targets live outside of `OUT_DIR`, so they are checked instead
+    // of generated.
+    for iteration in iterations {
+        writeln!(file)?;
+        writeln_indented!(file, 1, "#[test]")?;
+        writeln_indented!(file, 1, "fn {}_fuzz_target() {{", iteration)?;
+        writeln_indented!(file, 2, "assert_eq!(")?;
+
+        writeln_indented!(
+            file,
+            3,
+            r#"std::fs::read_to_string("{}/{}.rs").ok().as_deref(),"#,
+            FUZZ_TARGETS_DIR,
+            iteration
+        )?;
+
+        writeln_indented!(
+            file,
+            3,
+            r##"Some("#![no_main]\n\niterations::fuzz_target!({});\n"),"##,
+            iteration
+        )?;
+
+        writeln_indented!(
+            file,
+            3,
+            r#""fuzz target of {} should exist","#,
+            iteration
+        )?;
+
+        writeln_indented!(file, 2, ");\n")?;
+        writeln_indented!(file, 2, "assert!(")?;
+
+        writeln_indented!(
+            file,
+            3,
+            r#"std::fs::read_to_string("{}")"#,
+            FUZZ_MANIFEST
+        )?;
+
+        writeln_indented!(
+            file,
+            4,
+            r#".expect("fuzzing manifest should be readable")"#
+        )?;
+
+        writeln_indented!(
+            file,
+            4,
+            r#".contains("name = \"{0}\"\npath = \"fuzz_targets/{0}.rs\""),"#,
+            iteration
+        )?;
+
+        writeln_indented!(
+            file,
+            3,
+            r#""fuzzing manifest should have a [[bin]] entry for {}","#,
+            iteration
+        )?;
+
+        writeln_indented!(file, 2, ")")?;
+        writeln_indented!(file, 1, "}}")?;
+    }
+
     writeln_indented!(file, 0, "}}")?;
 
     Ok(())
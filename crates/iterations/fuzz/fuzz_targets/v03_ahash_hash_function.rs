@@ -0,0 +1,3 @@
+#![no_main]
+
+iterations::fuzz_target!(v03_ahash_hash_function);
@@ -0,0 +1,3 @@
+#![no_main]
+
+iterations::fuzz_target!(v04_rayon_work_stealing);
@@ -0,0 +1,3 @@
+#![no_main]
+
+iterations::fuzz_target!(v05_io_uring);
@@ -0,0 +1,3 @@
+#![no_main]
+
+iterations::fuzz_target!(v06_branchless_temperature_parsing);
@@ -0,0 +1,3 @@
+#![no_main]
+
+iterations::fuzz_target!(v07_interned_station_names);
@@ -0,0 +1,129 @@
+//! Global allocator wrapping the system allocator, counting the allocations,
+//! deallocations, reallocations, and peak heap usage while an iteration runs.
+
+use serde::Serialize;
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicU64, Ordering::Relaxed},
+};
+
+#[global_allocator]
+static ALLOCATOR: Counter = Counter;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+static DEALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static PEAK_BYTES: AtomicU64 = AtomicU64::new(0);
+static REALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Copy, Serialize)]
+pub struct Allocations {
+    /// Allocations, excluding reallocations.
+    pub allocations: u64,
+
+    pub deallocations: u64,
+
+    /// Peak of the allocated bytes, relative to before running.
+    pub peak_bytes: u64,
+
+    /// Reallocations, resizing an allocation without counting as an
+    /// allocation and a deallocation.
+    pub reallocations: u64,
+}
+
+struct Counter;
+
+impl Counter {
+    fn allocate(size: usize) {
+        ALLOCATIONS.fetch_add(1, Relaxed);
+        Self::grow(size);
+    }
+
+    fn deallocate(size: usize) {
+        DEALLOCATIONS.fetch_add(1, Relaxed);
+        Self::shrink(size);
+    }
+
+    fn grow(size: usize) {
+        let bytes = BYTES.fetch_add(size as u64, Relaxed) + size as u64;
+        PEAK_BYTES.fetch_max(bytes, Relaxed);
+    }
+
+    /// Count a reallocation by the change of its size, ignoring that the old
+    /// and new allocations may briefly coexist while copying.
+    fn reallocate(old_size: usize, new_size: usize) {
+        REALLOCATIONS.fetch_add(1, Relaxed);
+
+        if new_size > old_size {
+            Self::grow(new_size - old_size);
+        } else {
+            Self::shrink(old_size - new_size);
+        }
+    }
+
+    fn shrink(size: usize) {
+        BYTES.fetch_sub(size as u64, Relaxed);
+    }
+}
+
+// SAFETY: Every call is forwarded to the system allocator unchanged.
+unsafe impl GlobalAlloc for Counter {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::allocate(layout.size());
+
+        // SAFETY: The caller upholds the contract of `GlobalAlloc::alloc`.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        Self::allocate(layout.size());
+
+        // SAFETY: The caller upholds the contract of
+        // `GlobalAlloc::alloc_zeroed`.
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        Self::deallocate(layout.size());
+
+        // SAFETY: The caller upholds the contract of `GlobalAlloc::dealloc`.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(
+        &self,
+        ptr: *mut u8,
+        layout: Layout,
+        new_size: usize,
+    ) -> *mut u8 {
+        Self::reallocate(layout.size(), new_size);
+
+        // SAFETY: The caller upholds the contract of `GlobalAlloc::realloc`.
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+/// Run `f`, counting its allocations.
+///
+/// Allocations of other threads running concurrently are counted as well.
+pub fn count<T>(f: impl FnOnce() -> T) -> (T, Allocations) {
+    let allocations = ALLOCATIONS.load(Relaxed);
+    let bytes = BYTES.load(Relaxed);
+    let deallocations = DEALLOCATIONS.load(Relaxed);
+    let reallocations = REALLOCATIONS.load(Relaxed);
+
+    PEAK_BYTES.store(bytes, Relaxed);
+
+    let output = f();
+
+    (
+        output,
+        Allocations {
+            allocations: ALLOCATIONS.load(Relaxed) - allocations,
+            deallocations: DEALLOCATIONS.load(Relaxed) - deallocations,
+            peak_bytes: PEAK_BYTES.load(Relaxed).saturating_sub(bytes),
+            reallocations: REALLOCATIONS.load(Relaxed) - reallocations,
+        },
+    )
+}
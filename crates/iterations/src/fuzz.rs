@@ -0,0 +1,112 @@
+//! Differential fuzzing of an iteration against the base implementation.
+//!
+//! Arbitrary bytes are run through both the base implementation and the fuzzed
+//! iteration from an in-memory file, without touching the filesystem. Inputs
+//! rejected by an iteration must be rejected by panicking, and accepted inputs
+//! must produce the same output as the base implementation.
+//!
+//! The in-memory file is created with `memfd_create`, requiring Linux.
+
+use std::{
+    fs::File,
+    io,
+    os::{
+        fd::{AsRawFd, FromRawFd},
+        unix::fs::FileExt,
+    },
+    panic::{self, UnwindSafe},
+    path::PathBuf,
+    sync::{
+        LazyLock, Mutex, Once,
+        atomic::{AtomicUsize, Ordering::SeqCst},
+    },
+};
+
+use crate::{BASE, Iteration, run};
+
+/// In-memory file holding the current input, shared by the fuzzing threads.
+static INPUT: LazyLock<Mutex<(File, PathBuf)>> = LazyLock::new(|| {
+    // SAFETY: The name is a valid C string, and the returned file descriptor
+    // is checked before being owned.
+    let fd = unsafe { libc::memfd_create(c"iterations-fuzz".as_ptr(), 0) };
+
+    assert!(
+        fd >= 0,
+        "in-memory input file should be creatable: {}",
+        io::Error::last_os_error()
+    );
+
+    // SAFETY: The file descriptor is newly created and owned by nothing else.
+    let file = unsafe { File::from_raw_fd(fd) };
+    let path = PathBuf::from(format!("/proc/self/fd/{}", file.as_raw_fd()));
+
+    Mutex::new((file, path))
+});
+
+/// Number of calls to `catch` in progress, silencing panics on every thread
+/// while positive.
+static CATCHING: AtomicUsize = AtomicUsize::new(0);
+
+/// Run `f`, returning `None` if it panics.
+///
+/// Panics are silenced while `f` runs, since `libfuzzer-sys` installs a panic
+/// hook that aborts the process before unwinding. This includes panics of
+/// worker threads, like those of `rayon`, which propagate to the caller. The
+/// hook is replaced once instead of on every call.
+fn catch(f: impl FnOnce() -> String + UnwindSafe) -> Option<String> {
+    static HOOK: Once = Once::new();
+
+    HOOK.call_once(|| {
+        let hook = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            if CATCHING.load(SeqCst) == 0 {
+                hook(info);
+            }
+        }));
+    });
+
+    CATCHING.fetch_add(1, SeqCst);
+    let output = panic::catch_unwind(f).ok();
+    CATCHING.fetch_sub(1, SeqCst);
+
+    output
+}
+
+/// Run `iteration` with `data` as its input file, held in memory.
+pub fn run_bytes(iteration: Iteration, data: &[u8]) -> String {
+    let input = INPUT.lock().unwrap_or_else(|error| error.into_inner());
+    let (file, path) = &*input;
+
+    file.set_len(0)
+        .expect("in-memory input should be truncatable");
+
+    file.write_all_at(data, 0)
+        .expect("in-memory input should be writable");
+
+    run(iteration, path)
+}
+
+/// Run `iteration` and the base implementation with `data` as their input,
+/// panicking if `iteration` accepts it with a different output.
+pub fn fuzz(iteration: &Iteration, data: &[u8]) {
+    let expected = catch(|| run_bytes(BASE, data));
+
+    if let Some(actual) = catch(|| run_bytes(iteration.clone(), data)) {
+        assert_eq!(
+            expected.as_deref(),
+            Some(actual.as_str()),
+            "{iteration} should match {BASE} or panic",
+        );
+    }
+}
+
+/// Define the `libFuzzer` entry point fuzzing `$iteration`.
+#[macro_export]
+macro_rules! fuzz_target {
+    ($iteration:ident) => {
+        libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+            $crate::fuzz::fuzz(&$crate::Iteration::$iteration, data)
+        });
+    };
+}
@@ -0,0 +1,112 @@
+//! Input files with their size in bytes and rows, as used for measuring
+//! throughput.
+
+use itertools::Itertools;
+use tap::{Pipe, Tap};
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+pub struct Input {
+    pub bytes: u64,
+    pub path: PathBuf,
+    pub rows: u64,
+}
+
+impl Input {
+    pub fn new(path: PathBuf) -> io::Result<Self> {
+        let mut file = File::open(&path)?.pipe(BufReader::new);
+
+        let mut bytes = 0;
+        let mut last = None;
+        let mut rows = 0;
+
+        loop {
+            let buffer = file.fill_buf()?;
+
+            let Some(&byte) = buffer.last() else {
+                break;
+            };
+
+            rows += buffer.iter().filter(|&&byte| byte == b'\n').count() as u64;
+            last = Some(byte);
+
+            let length = buffer.len();
+            bytes += length as u64;
+            file.consume(length);
+        }
+
+        Ok(Self {
+            bytes,
+            path,
+
+            // Count a trailing line without a newline character (b'\n').
+            rows: rows + last.is_some_and(|byte| byte != b'\n') as u64,
+        })
+    }
+
+    /// Collect the inputs from files and directories of files, sorted by their
+    /// number of rows.
+    pub fn from_paths(
+        paths: impl IntoIterator<Item = PathBuf>,
+    ) -> io::Result<Vec<Self>> {
+        paths
+            .into_iter()
+            .map(|path| -> io::Result<Vec<PathBuf>> {
+                if path.is_dir() {
+                    fs::read_dir(path)?
+                        .map(|entry| entry.map(|entry| entry.path()))
+                        .collect()
+                } else {
+                    Ok(vec![path])
+                }
+            })
+            .flatten_ok()
+            .collect::<io::Result<Vec<_>>>()?
+            .into_iter()
+            .sorted()
+            .dedup()
+            .map(Self::new)
+            .collect::<io::Result<Vec<_>>>()?
+            .tap_mut(|inputs| {
+                inputs.sort_by(|a, b| {
+                    a.rows.cmp(&b.rows).then(a.path.cmp(&b.path))
+                })
+            })
+            .pipe(Ok)
+    }
+
+    pub fn name(&self) -> &str {
+        self.path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .expect("filename stem should be valid UTF-8")
+    }
+}
+
+/// Evict the cached pages of the file at `path` with
+/// `posix_fadvise(POSIX_FADV_DONTNEED)`, so that the next read of it is served
+/// by the storage device instead of the page cache.
+#[cfg(target_os = "linux")]
+pub fn drop_page_cache(path: &Path) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let file = File::open(path)?;
+
+    // SAFETY: The file descriptor is valid for the lifetime of `file`.
+    match unsafe {
+        libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED)
+    } {
+        0 => Ok(()),
+        error => Err(io::Error::from_raw_os_error(error)),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn drop_page_cache(_: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "dropping the page cache requires Linux",
+    ))
+}
@@ -0,0 +1,258 @@
+//! Read the whole file into memory and split it into newline-aligned chunks,
+//! parsed in parallel by `rayon`'s work-stealing scheduler into per-thread
+//! hash maps, which are merged pairwise in a reduction tree.
+//!
+//! This measures the overhead of a general-purpose scheduler, rather than
+//! improving on a manual split of the input across threads.
+//!
+//! Merging partial sums changes the order of floating-point additions, so a
+//! mean exactly halfway between two tenths may round differently than in the
+//! sequential iterations once the input spans several chunks.
+
+use itertools::Itertools;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use tap::{Pipe, Tap};
+
+use ahash::AHashMap;
+use std::collections::hash_map::Entry;
+use std::fmt::{self, Display};
+use std::fs;
+use std::path::Path;
+use std::str::{from_utf8, from_utf8_unchecked};
+
+type StationName = Vec<u8>;
+type Stations = AHashMap<StationName, Station>;
+type Temperature = f64;
+
+/// Chunks per thread, letting idle threads steal work from slower ones.
+const CHUNKS_PER_THREAD: usize = 16;
+
+/// Minimum chunk size in bytes, amortizing the cost of scheduling a chunk and
+/// merging its hash map.
+const MIN_CHUNK_SIZE: usize = 1 << 20;
+
+struct Station {
+    count: u64,
+    max: Temperature,
+    min: Temperature,
+    sum: Temperature,
+}
+
+impl Station {
+    fn new(value: Temperature) -> Self {
+        Self {
+            count: 1,
+            max: value,
+            min: value,
+            sum: value,
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.count += other.count;
+        self.sum += other.sum;
+        self.max = self.max.max(other.max);
+        self.min = self.min.min(other.min);
+    }
+
+    fn update(&mut self, value: Temperature) {
+        self.count += 1;
+        self.sum += value;
+        self.max = self.max.max(value);
+        self.min = self.min.min(value);
+    }
+}
+
+impl Display for Station {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const PRECISION: Temperature = 10.0;
+
+        write!(
+            f,
+            "{:.1}/{:.1}/{:.1}",
+            self.min,
+            (self.sum * PRECISION / self.count as Temperature).round()
+                / PRECISION
+
+                // Add 0.0 to display -0.0 as 0.0, since IEEE 754 rounding
+                // produces signed zeros.
+                + 0.0,
+            self.max
+        )
+    }
+}
+
+/// Split `bytes` into at most about `count` chunks of at least `min_size`
+/// bytes, each ending after a newline character (b'\n') except possibly the
+/// last one.
+fn chunks(bytes: &[u8], count: usize, min_size: usize) -> Vec<&[u8]> {
+    let size = bytes.len().div_ceil(count).max(min_size);
+    let mut chunks = Vec::with_capacity(count);
+    let mut rest = bytes;
+
+    while !rest.is_empty() {
+        let end = rest
+            .get(size..)
+            .and_then(|tail| tail.iter().position(|&byte| byte == b'\n'))
+            .map_or(rest.len(), |position| size + position + 1);
+
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+
+    chunks
+}
+
+fn parse(mut stations: Stations, chunk: &[u8]) -> Stations {
+    chunk
+        .strip_suffix(b"\n")
+        .unwrap_or(chunk)
+        .split(|&byte| byte == b'\n')
+        .for_each(|line| {
+            line.split_once(|&byte| byte == b';')
+                .expect("line should contain exactly one semicolon (';')")
+                .pipe(|(station, temperature)| {
+                    (
+                        station,
+                        temperature
+                            .pipe(|temperature|
+                                // SAFETY: The `temperature` must be a valid
+                                // `Temperature`, without necessarily being
+                                // UTF-8 valid.
+                                unsafe { from_utf8_unchecked(temperature) })
+                            .parse::<Temperature>()
+                            .expect("temperature should be a float"),
+                    )
+                })
+                .pipe(|(station, temperature)| {
+                    stations
+                        .entry(station.into())
+                        .and_modify(|station| station.update(temperature))
+                        .or_insert_with(|| Station::new(temperature))
+                });
+        });
+
+    stations
+}
+
+/// Merge the smaller of `left` and `right` into the larger one.
+fn merge(left: Stations, right: Stations) -> Stations {
+    let (mut into, from) = if left.len() < right.len() {
+        (right, left)
+    } else {
+        (left, right)
+    };
+
+    from.into_iter().for_each(|(name, station)| {
+        match into.entry(name) {
+            Entry::Occupied(mut entry) => entry.get_mut().merge(&station),
+            Entry::Vacant(entry) => {
+                entry.insert(station);
+            }
+        }
+    });
+
+    into
+}
+
+/// Parse `bytes` in parallel, in chunks of at least `min_chunk_size` bytes.
+fn stations(bytes: &[u8], min_chunk_size: usize) -> Stations {
+    chunks(
+        bytes,
+        rayon::current_num_threads() * CHUNKS_PER_THREAD,
+        min_chunk_size,
+    )
+    .into_par_iter()
+    .fold(Stations::new, parse)
+    .reduce(Stations::new, merge)
+}
+
+pub fn v04_rayon_work_stealing(input: &Path) -> String {
+    let bytes = fs::read(input).expect("input file should be readable");
+
+    stations(&bytes, MIN_CHUNK_SIZE)
+        .iter()
+        .collect::<Vec<_>>()
+        .tap_mut(|stations| stations.sort_unstable_by_key(|&(name, _)| name))
+        .iter()
+        .map(|(name, station)| {
+            format!(
+                "{}: {}",
+                from_utf8(name).expect("station name should be UTF-8 valid"),
+                station
+            )
+        })
+        .join(", ")
+        .pipe(|output| format!("{{{output}}}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REALISTIC: &[u8] =
+        include_bytes!("../../data/realistic_measurements_16384_lines.txt");
+
+    const ROUNDING: &[u8] = include_bytes!("../../data/temperature_rounding.txt");
+
+    /// Chunk size splitting the test inputs into many chunks.
+    const SMALL_CHUNK_SIZE: usize = 16;
+
+    /// Run `f` on several threads, regardless of the available parallelism.
+    fn parallel<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .expect("thread pool should be buildable")
+            .install(f)
+    }
+
+    fn sequential(bytes: &[u8]) -> Stations {
+        parse(Stations::new(), bytes)
+    }
+
+    #[test]
+    fn chunks_end_after_newlines() {
+        let chunks = chunks(REALISTIC, 64, SMALL_CHUNK_SIZE);
+
+        assert!(chunks.len() > 1, "input should span several chunks");
+        assert_eq!(chunks.concat(), REALISTIC);
+
+        for chunk in &chunks[..chunks.len() - 1] {
+            assert_eq!(chunk.last(), Some(&b'\n'));
+        }
+    }
+
+    #[test]
+    fn merged_chunks_match_sequential_parse() {
+        let merged = parallel(|| stations(ROUNDING, SMALL_CHUNK_SIZE));
+        let sequential = sequential(ROUNDING);
+
+        assert_eq!(merged.len(), sequential.len());
+
+        for (name, station) in &sequential {
+            assert_eq!(merged[name].to_string(), station.to_string());
+        }
+    }
+
+    /// Merged partial sums only differ from the sequential sum by rounding
+    /// errors, which may flip the rounding of a mean exactly halfway between
+    /// two tenths.
+    #[test]
+    fn merged_chunks_only_differ_in_rounding() {
+        let merged = parallel(|| stations(REALISTIC, SMALL_CHUNK_SIZE));
+        let sequential = sequential(REALISTIC);
+
+        assert_eq!(merged.len(), sequential.len());
+
+        for (name, station) in &sequential {
+            let other = &merged[name];
+
+            assert_eq!(other.count, station.count);
+            assert_eq!(other.max, station.max);
+            assert_eq!(other.min, station.min);
+            assert!((other.sum - station.sum).abs() < 1e-9);
+        }
+    }
+}
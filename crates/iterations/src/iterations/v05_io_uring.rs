@@ -0,0 +1,362 @@
+//! Read the file with `io_uring`, keeping several fixed-size buffers in flight
+//! so that the kernel reads ahead while previous buffers are parsed, instead of
+//! blocking on a synchronous read system call per buffer.
+//!
+//! This targets cold-cache runs, where reads are bound by the storage device.
+//! Without `io_uring`, like on other platforms or when it is disabled, buffers
+//! are read one after another with `pread`.
+
+use itertools::Itertools;
+use tap::{Pipe, Tap};
+
+use ahash::AHashMap;
+use std::fmt::{self, Display};
+use std::fs::File;
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+use std::str::{from_utf8, from_utf8_unchecked};
+
+type StationName = Vec<u8>;
+type Temperature = f64;
+
+/// Size of each buffer in bytes.
+#[cfg(not(test))]
+const BUFFER_SIZE: usize = 1 << 20;
+
+/// Size of each buffer in bytes while testing, reading the test inputs in
+/// many buffers to exercise their rotation and carrying lines over.
+#[cfg(test)]
+const BUFFER_SIZE: usize = 16;
+
+/// Number of buffers read concurrently.
+const BUFFERS: usize = 4;
+
+struct Station {
+    count: u64,
+    max: Temperature,
+    min: Temperature,
+    sum: Temperature,
+}
+
+impl Station {
+    fn new(value: Temperature) -> Self {
+        Self {
+            count: 1,
+            max: value,
+            min: value,
+            sum: value,
+        }
+    }
+
+    fn update(&mut self, value: Temperature) {
+        self.count += 1;
+        self.sum += value;
+        self.max = self.max.max(value);
+        self.min = self.min.min(value);
+    }
+}
+
+impl Display for Station {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const PRECISION: Temperature = 10.0;
+
+        write!(
+            f,
+            "{:.1}/{:.1}/{:.1}",
+            self.min,
+            (self.sum * PRECISION / self.count as Temperature).round()
+                / PRECISION
+
+                // Add 0.0 to display -0.0 as 0.0, since IEEE 754 rounding
+                // produces signed zeros.
+                + 0.0,
+            self.max
+        )
+    }
+}
+
+/// Parser of consecutive buffers, carrying the incomplete last line of a
+/// buffer over to the next one.
+#[derive(Default)]
+struct Parser {
+    line: Vec<u8>,
+    stations: AHashMap<StationName, Station>,
+}
+
+impl Parser {
+    fn parse(&mut self, buffer: &[u8]) {
+        let Some(last) = buffer.iter().rposition(|&byte| byte == b'\n') else {
+            self.line.extend_from_slice(buffer);
+            return;
+        };
+
+        let (lines, rest) = buffer.split_at(last + 1);
+
+        let lines = if self.line.is_empty() {
+            lines
+        } else {
+            let (first, lines) = lines
+                .split_once(|&byte| byte == b'\n')
+                .expect("buffer should contain a newline character (b'\\n')");
+
+            self.line.extend_from_slice(first);
+            update(&mut self.stations, &self.line);
+            self.line.clear();
+
+            lines
+        };
+
+        // Exclude the trailing newline character (b'\n').
+        if let Some(lines) = lines.strip_suffix(b"\n") {
+            lines
+                .split(|&byte| byte == b'\n')
+                .for_each(|line| update(&mut self.stations, line));
+        }
+
+        self.line.extend_from_slice(rest);
+    }
+
+    /// Parse the last line, not ending with a newline character (b'\n').
+    fn finish(mut self) -> AHashMap<StationName, Station> {
+        if !self.line.is_empty() {
+            update(&mut self.stations, &self.line);
+        }
+
+        self.stations
+    }
+}
+
+fn update(stations: &mut AHashMap<StationName, Station>, line: &[u8]) {
+    line.split_once(|&byte| byte == b';')
+        .expect("line should contain exactly one semicolon (';')")
+        .pipe(|(station, temperature)| {
+            (
+                station,
+                temperature
+                    .pipe(|temperature|
+                        // SAFETY: The `temperature` must be a valid
+                        // `Temperature`, without necessarily being UTF-8
+                        // valid.
+                        unsafe { from_utf8_unchecked(temperature) })
+                    .parse::<Temperature>()
+                    .expect("temperature should be a float"),
+            )
+        })
+        .pipe(|(station, temperature)| {
+            stations
+                .entry(station.into())
+                .and_modify(|station| station.update(temperature))
+                .or_insert_with(|| Station::new(temperature))
+        });
+}
+
+/// Read the whole `file` with `pread`, one buffer at a time.
+fn pread(file: &File, parser: &mut Parser) {
+    let mut buffer = vec![0; BUFFER_SIZE];
+    let mut offset = 0;
+
+    loop {
+        let bytes = file
+            .read_at(&mut buffer, offset)
+            .expect("input file should be readable");
+
+        if bytes == 0 {
+            break;
+        }
+
+        parser.parse(&buffer[..bytes]);
+        offset += bytes as u64;
+    }
+}
+
+/// Read the whole `file` with `io_uring`, parsing the buffers in file order as
+/// their reads complete, and resubmitting each parsed buffer for the next
+/// unread range. Returns `false` if `io_uring` is unavailable.
+#[cfg(target_os = "linux")]
+fn io_uring(file: &File, parser: &mut Parser) -> bool {
+    use io_uring::{IoUring, cqueue, opcode, types::Fd};
+    use std::{io::ErrorKind, os::fd::AsRawFd, process};
+
+    /// Pending read of `length` bytes at `offset`, of which `filled` bytes
+    /// have been read.
+    #[derive(Clone, Copy, Default)]
+    struct Read {
+        filled: usize,
+        length: usize,
+        offset: u64,
+    }
+
+    /// Ring counting its reads in flight, and waiting for them when dropped.
+    ///
+    /// Dropping `IoUring` does not wait for pending reads, so without waiting,
+    /// a panic while parsing would free the buffers before the kernel is done
+    /// writing into them.
+    struct Ring {
+        in_flight: usize,
+        ring: IoUring,
+    }
+
+    impl Ring {
+        /// Submit the unread range of `read` into the buffer at `index`.
+        fn submit(
+            &mut self,
+            file: &File,
+            buffers: &mut [Vec<u8>],
+            index: usize,
+            read: Read,
+        ) {
+            let buffer = &mut buffers[index][read.filled..read.length];
+
+            let entry = opcode::Read::new(
+                Fd(file.as_raw_fd()),
+                buffer.as_mut_ptr(),
+                buffer.len() as u32,
+            )
+            .offset(read.offset + read.filled as u64)
+            .build()
+            .user_data(index as u64);
+
+            // SAFETY: The buffer stays allocated and untouched until its read
+            // completes, since the ring is dropped before the buffers and
+            // waits for every read in flight, and the ring has room for one
+            // entry per buffer.
+            unsafe { self.ring.submission().push(&entry) }
+                .expect("submission queue should have room for every buffer");
+
+            self.in_flight += 1;
+        }
+
+        /// Wait for at least one read to complete, returning the completed
+        /// ones.
+        fn complete(&mut self) -> Vec<cqueue::Entry> {
+            self.ring
+                .submit_and_wait(1)
+                .expect("reads should be submittable");
+
+            let entries = self.ring.completion().collect::<Vec<_>>();
+            self.in_flight -= entries.len();
+
+            entries
+        }
+    }
+
+    impl Drop for Ring {
+        fn drop(&mut self) {
+            while self.in_flight > 0 {
+                match self.ring.submit_and_wait(1) {
+                    Ok(_) => self.in_flight -= self.ring.completion().count(),
+                    Err(error) if error.kind() == ErrorKind::Interrupted => {}
+
+                    // Freeing the buffers of reads that may still complete
+                    // would be a use after free.
+                    Err(_) => process::abort(),
+                }
+            }
+        }
+    }
+
+    // Declared before the ring, so that they are dropped after it.
+    let mut buffers = vec![vec![0_u8; BUFFER_SIZE]; BUFFERS];
+
+    let Ok(ring) = IoUring::new(BUFFERS as u32) else {
+        return false;
+    };
+
+    let mut ring = Ring { in_flight: 0, ring };
+
+    let size = file
+        .metadata()
+        .expect("input file metadata should be readable")
+        .len();
+
+    let mut reads = [Read::default(); BUFFERS];
+    let mut next = 0;
+
+    for (index, read) in reads.iter_mut().enumerate() {
+        if next >= size {
+            break;
+        }
+
+        *read = Read {
+            filled: 0,
+            length: (size - next).min(BUFFER_SIZE as u64) as usize,
+            offset: next,
+        };
+
+        ring.submit(file, &mut buffers, index, *read);
+        next += read.length as u64;
+    }
+
+    // Buffers are parsed in file order, which cycles through them.
+    let mut current = 0;
+
+    while reads[current].length > 0 {
+        while reads[current].filled < reads[current].length {
+            for entry in ring.complete() {
+                let index = entry.user_data() as usize;
+
+                let bytes = usize::try_from(entry.result())
+                    .expect("input file should be readable");
+
+                assert!(bytes > 0, "input file should not shrink");
+
+                reads[index].filled += bytes;
+
+                // Resubmit the remainder of a short read.
+                if reads[index].filled < reads[index].length {
+                    ring.submit(file, &mut buffers, index, reads[index]);
+                }
+            }
+        }
+
+        parser.parse(&buffers[current][..reads[current].length]);
+
+        reads[current] = if next < size {
+            let read = Read {
+                filled: 0,
+                length: (size - next).min(BUFFER_SIZE as u64) as usize,
+                offset: next,
+            };
+
+            ring.submit(file, &mut buffers, current, read);
+            next += read.length as u64;
+            read
+        } else {
+            Read::default()
+        };
+
+        current = (current + 1) % BUFFERS;
+    }
+
+    true
+}
+
+#[cfg(not(target_os = "linux"))]
+fn io_uring(_: &File, _: &mut Parser) -> bool {
+    false
+}
+
+pub fn v05_io_uring(input: &Path) -> String {
+    let file = File::open(input).expect("input file should be readable");
+    let mut parser = Parser::default();
+
+    if !io_uring(&file, &mut parser) {
+        pread(&file, &mut parser);
+    }
+
+    parser
+        .finish()
+        .iter()
+        .collect::<Vec<_>>()
+        .tap_mut(|stations| stations.sort_unstable_by_key(|&(name, _)| name))
+        .iter()
+        .map(|(name, station)| {
+            format!(
+                "{}: {}",
+                from_utf8(name).expect("station name should be UTF-8 valid"),
+                station
+            )
+        })
+        .join(", ")
+        .pipe(|output| format!("{{{output}}}"))
+}
@@ -1,4 +1,12 @@
 #![feature(slice_split_once)]
 
+#[cfg(feature = "allocations")]
+pub mod allocations;
+
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+
+pub mod input;
+
 include!(concat!(env!("OUT_DIR"), "/iterations.rs"));
 mod_and_use!();
@@ -18,9 +18,30 @@ struct Cli {
         required = true,
     )]
     input: PathBuf,
+
+    /// Print the number of allocations, deallocations, and reallocations, and
+    /// the peak allocated bytes of the iteration to stderr.
+    #[cfg(feature = "allocations")]
+    #[arg(long)]
+    allocations: bool,
 }
 
 fn main() {
     let cli = Cli::parse();
+
+    #[cfg(feature = "allocations")]
+    if cli.allocations {
+        let (output, allocations) =
+            iterations::allocations::count(|| run(cli.iteration, &cli.input));
+
+        println!("{output}");
+        eprintln!("allocations: {}", allocations.allocations);
+        eprintln!("deallocations: {}", allocations.deallocations);
+        eprintln!("reallocations: {}", allocations.reallocations);
+        eprintln!("peak bytes: {}", allocations.peak_bytes);
+
+        return;
+    }
+
     println!("{}", run(cli.iteration, &cli.input));
 }
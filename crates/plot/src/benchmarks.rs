@@ -1,16 +1,302 @@
+use clap::ValueEnum;
 use serde::Deserialize;
 
+use std::fmt::{self, Display};
+
+pub use criterion_messages::Estimate;
+
+pub type Bytes = u64;
+pub type Count = u64;
 pub type Input = i64;
 pub type Time = f64;
 
+const SIGNIFICANT_DIGITS: i32 = 4;
+
+/// Units of time, from the largest to the smallest.
+const TIME_UNITS: [(Time, &str); 4] =
+    [(1.0, "s"), (1e-3, "ms"), (1e-6, "µs"), (1e-9, "ns")];
+
 #[derive(Deserialize)]
 pub struct Benchmark {
+    /// Size of the input file, unknown for older datasets.
+    pub bytes: Option<Bytes>,
+
+    /// Hardware performance counters of a single run, unknown unless requested
+    /// and available.
+    pub counters: Option<Counters>,
+
     pub input: Input,
+
+    /// Mean and median time with their confidence intervals, and the standard
+    /// deviation of the time, all unknown for older datasets.
+    pub mean: Option<Estimate>,
+    pub median: Option<Estimate>,
+    pub std_dev: Option<Time>,
+
     pub time: Time,
 }
 
 #[derive(Deserialize)]
 pub struct Benchmarks {
     pub benchmarks: Vec<Benchmark>,
+
+    /// Benchmarking system, unknown for datasets ingested from criterion
+    /// output.
+    #[serde(default)]
+    pub metadata: Option<Metadata>,
     pub name: String,
 }
+
+/// Counts of user-space events, of which only the instructions are plotted,
+/// unknown if their counter was unavailable.
+#[derive(Clone, Copy, Deserialize)]
+pub struct Counters {
+    pub instructions: Option<Count>,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct Cpu {
+    pub cores: u64,
+    pub name: Option<String>,
+    pub threads: u64,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct Gpu {
+    pub name: Option<String>,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct Hardware {
+    pub architecture: String,
+    pub cpu: Cpu,
+    pub gpu: Gpu,
+    pub ram: Ram,
+    pub storage: Storage,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct Kernel {
+    pub name: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Benchmark metadata to group datasets from several machines by.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Key {
+    Architecture,
+    Commit,
+    Cpu,
+    Filesystem,
+    Gpu,
+
+    /// All hardware details.
+    Hardware,
+
+    Kernel,
+    Os,
+    Ram,
+    Rustc,
+
+    /// All software details.
+    Software,
+
+    Storage,
+}
+
+impl Key {
+    /// Value of the key in `metadata`, if known.
+    pub fn value(self, metadata: &Metadata) -> Option<String> {
+        let Metadata {
+            commit,
+            hardware,
+            software,
+        } = metadata;
+
+        match self {
+            Self::Architecture => Some(hardware.architecture.clone()),
+            Self::Commit => Some(commit.clone()),
+            Self::Cpu => hardware.cpu.name.clone(),
+            Self::Filesystem => software.filesystem.clone(),
+            Self::Gpu => hardware.gpu.name.clone(),
+            Self::Hardware => Some(hardware.to_string()),
+
+            Self::Kernel => Some(words([
+                software.kernel.name.as_deref(),
+                software.kernel.version.as_deref(),
+            ])),
+
+            Self::Os => Some(words([
+                software.os.name.as_deref(),
+                software.os.version.as_deref(),
+            ])),
+
+            Self::Ram => Some(words([
+                hardware.ram.size.as_deref(),
+                hardware.ram.kind.as_deref(),
+                hardware.ram.frequency.as_deref(),
+            ])),
+
+            Self::Rustc => Some(software.rust.rustc.clone()),
+            Self::Software => Some(software.to_string()),
+            Self::Storage => hardware.storage.name.clone(),
+        }
+        .filter(|value| !value.is_empty())
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct Metadata {
+    pub commit: String,
+    pub hardware: Hardware,
+    pub software: Software,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct Os {
+    pub name: Option<String>,
+    pub version: Option<String>,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct Ram {
+    pub frequency: Option<String>,
+    pub name: Option<String>,
+    pub size: Option<String>,
+
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct Rust {
+    pub cargo: String,
+    pub rustc: String,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct Software {
+    pub filesystem: Option<String>,
+    pub kernel: Kernel,
+    pub os: Os,
+    pub rust: Rust,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct Storage {
+    pub name: Option<String>,
+    pub size: Option<String>,
+}
+
+impl Metadata {
+    /// Whether both benchmarks ran on the same hardware and software,
+    /// regardless of their commit.
+    pub fn compatible(&self, other: &Self) -> bool {
+        self.hardware == other.hardware && self.software == other.software
+    }
+}
+
+/// Whether the datasets ran on the same hardware and software, with unknown
+/// metadata only compatible with unknown metadata.
+pub fn compatible(a: Option<&Metadata>, b: Option<&Metadata>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.compatible(b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Join the available `parts` with spaces.
+fn words<'a>(parts: impl IntoIterator<Item = Option<&'a str>>) -> String {
+    parts.into_iter().flatten().collect::<Vec<_>>().join(" ")
+}
+
+impl Display for Hardware {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}, {} cores, {} threads), {} RAM",
+            self.cpu.name.as_deref().unwrap_or("Unknown CPU"),
+            self.architecture,
+            self.cpu.cores,
+            self.cpu.threads,
+            words([
+                self.ram.size.as_deref(),
+                self.ram.kind.as_deref(),
+                self.ram.frequency.as_deref(),
+            ]),
+        )?;
+
+        if let Some(storage) = &self.storage.name {
+            write!(f, ", {storage}")?;
+
+            if let Some(size) = &self.storage.size {
+                write!(f, " ({size})")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Software {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}, {}",
+            words([self.os.name.as_deref(), self.os.version.as_deref()]),
+            words([
+                self.kernel.name.as_deref(),
+                self.kernel.version.as_deref(),
+            ]),
+        )?;
+
+        if let Some(filesystem) = &self.filesystem {
+            write!(f, ", {filesystem}")?;
+        }
+
+        write!(f, ", {}", self.rust.rustc)
+    }
+}
+
+/// Format a time in seconds with the largest unit it reaches, or unscaled if it
+/// isn't a positive number, such as an extrapolated fit below zero.
+pub fn format_time(seconds: Time) -> String {
+    if !(seconds.is_finite() && seconds > 0.0) {
+        return format!("{seconds} s");
+    }
+
+    let (factor, unit) = TIME_UNITS
+        .into_iter()
+        .find(|(factor, _)| seconds >= *factor)
+        .unwrap_or(TIME_UNITS[TIME_UNITS.len() - 1]);
+
+    let value = seconds / factor;
+
+    let precision =
+        (SIGNIFICANT_DIGITS - 1 - value.log10().floor() as i32).max(0) as usize;
+
+    format!("{value:.precision$} {unit}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn times_are_formatted_with_largest_unit() {
+        assert_eq!(format_time(1.5), "1.500 s");
+        assert_eq!(format_time(0.012_34), "12.34 ms");
+        assert_eq!(format_time(123.4e-6), "123.4 µs");
+        assert_eq!(format_time(5e-12), "0.005000 ns");
+        assert_eq!(format_time(12_345.0), "12345 s");
+    }
+
+    #[test]
+    fn non_positive_times_are_formatted_unscaled() {
+        assert_eq!(format_time(0.0), "0 s");
+        assert_eq!(format_time(-0.002), "-0.002 s");
+        assert_eq!(format_time(Time::INFINITY), "inf s");
+        assert_eq!(format_time(Time::NAN), "NaN s");
+    }
+}
@@ -1,42 +1,112 @@
 use clap::{
-    Parser,
-    ValueHint::{DirPath, FilePath},
+    Args, Parser, Subcommand, ValueEnum,
+    ValueHint::{AnyPath, DirPath, FilePath},
+    value_parser,
 };
 
-use tap::Pipe;
+use itertools::Itertools;
 
 use std::{
-    fs::File,
-    io::BufReader,
+    collections::HashMap,
+    fmt::{self, Display},
     ops::Range,
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
-use crate::benchmarks::{Benchmarks, Input, Time};
+use crate::{
+    benchmarks::{
+        Benchmark, Benchmarks, Bytes, Count, Estimate, Input, Key, Metadata,
+        Time, compatible,
+    },
+    criterion,
+    error::{self, Error},
+    output::{Format, Grid, Output},
+    style::{Color, Legend, LineStyle, Marker, Style, Theme},
+    table::Markup,
+};
+
+/// Minimum resolution of the plots, a quarter of the reference resolution.
+const MIN_DPI: f64 = 24.0;
+
+/// Minimum width and height of the plots in pixels at 96 DPI, leaving room
+/// for the caption and axis labels.
+const MIN_SIZE: i64 = 100;
 
-const LINEAR_FILENAME: &str = "linear.svg";
-const LOG_FILENAME: &str = "log.svg";
+const NANOSECONDS_PER_SECOND: f64 = 1e9;
 
-type Error = Box<dyn std::error::Error>;
-type XRange = Range<Input>;
-type YRange = Range<Time>;
+/// Group of datasets without the metadata value grouped by.
+const UNKNOWN_GROUP: &str = "unknown";
+
+pub type XRange = Range<Input>;
+pub type YRange = Range<Time>;
 
 type DataPointsCollection = Vec<DataPoints>;
 
 #[derive(Parser)]
-#[command(version, about, long_about = None)]
+#[command(
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true,
+)]
 pub struct Cli {
-    /// Base of the logarithmic scale for the x-axis in the log plot.
-    #[arg(short, long, value_name = "BASE", default_value = "2")]
-    log_base: f64,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    datasets: Datasets,
+
+    #[command(flatten)]
+    style: StyleArgs,
+
+    /// Name of the dataset to additionally plot the speedups of all datasets
+    /// against.
+    #[arg(short, long, value_name = "NAME")]
+    baseline: Option<String>,
+
+    /// Number of columns of the grid layout, defaulting to a roughly square
+    /// grid.
+    #[arg(long, value_name = "COLUMNS")]
+    columns: Option<usize>,
+
+    /// Resolution of the plots, scaling their size, fonts, and lines.
+    #[arg(long, value_name = "DPI", default_value = "96", value_parser = dpi)]
+    dpi: f64,
 
-    /// Path to the JSON datasets.
+    /// Input size to extrapolate the fitted models to.
+    #[arg(long, value_name = "ROWS", default_value = "1000000000")]
+    extrapolate: Input,
+
+    /// Template of the plot filenames, substituting `{name}` with the plot
+    /// name and `{extension}` with the format extension.
+    #[arg(long, value_name = "TEMPLATE", default_value = "{name}.{extension}")]
+    filename_template: String,
+
+    /// Smallest input size to fit the models to, excluding smaller inputs
+    /// dominated by constant overhead.
+    #[arg(long, value_name = "ROWS", default_value = "65536")]
+    fit_min_input: Input,
+
+    /// File format of the plots.
+    #[arg(short, long, value_name = "FORMAT", default_value = "svg")]
+    format: Format,
+
+    /// Layout of the line plots, drawing a panel per dataset or per pair of
+    /// consecutive datasets with shared axes in a single image. Ignored in
+    /// terminal mode.
+    #[arg(long, value_name = "LAYOUT", default_value = "none")]
+    grid: Grid,
+
+    /// Height of the plots in pixels at 96 DPI.
     #[arg(
-        value_name = "INPUT",
-        value_hint = FilePath,
-        required = true,
+        long,
+        value_name = "PIXELS",
+        default_value = "1000",
+        value_parser = value_parser!(u32).range(MIN_SIZE..)
     )]
-    input: Vec<PathBuf>,
+    height: u32,
 
     /// Directory to write the generated plots to.
     #[arg(
@@ -48,6 +118,40 @@ pub struct Cli {
     )]
     output_directory: String,
 
+    /// Plots to generate, skipping the byte throughput plot without input
+    /// sizes in bytes, the instructions per row plot without hardware
+    /// performance counters, and the speedup plots without a baseline.
+    #[arg(
+        long,
+        value_name = "PLOT",
+        value_delimiter = ',',
+        default_values = [
+            "time",
+            "throughput-rows",
+            "throughput-bytes",
+            "instructions-per-row",
+            "speedup",
+            "speedup-summary",
+            "fit",
+            "report",
+        ],
+    )]
+    plots: Vec<Plot>,
+
+    /// Print the plots to the terminal instead of writing image files,
+    /// skipping the HTML report.
+    #[arg(long)]
+    terminal: bool,
+
+    /// Width of the plots in pixels at 96 DPI.
+    #[arg(
+        long,
+        value_name = "PIXELS",
+        default_value = "1000",
+        value_parser = value_parser!(u32).range(MIN_SIZE..)
+    )]
+    width: u32,
+
     /// Minimum value for the x-axis.
     #[arg(long, value_name = "X_MIN")]
     x_min: Option<Input>,
@@ -56,6 +160,17 @@ pub struct Cli {
     #[arg(long, value_name = "X_MAX")]
     x_max: Option<Input>,
 
+    /// Scales of the x-axis, as `linear`, `log`, or `log<BASE>` like `log2`,
+    /// generating each line plot once per combination of x- and y-axis
+    /// scales.
+    #[arg(
+        long,
+        value_name = "SCALE",
+        value_delimiter = ',',
+        default_values = ["linear", "log2"],
+    )]
+    x_scale: Vec<Scale>,
+
     /// Minimum value for the y-axis.
     #[arg(long, value_name = "Y_MIN")]
     y_min: Option<Time>,
@@ -63,105 +178,1044 @@ pub struct Cli {
     /// Maximum value for the y-axis.
     #[arg(long, value_name = "Y_MAX")]
     y_max: Option<Time>,
+
+    /// Scales of the y-axis, in the format of the x-axis scales.
+    #[arg(
+        long,
+        value_name = "SCALE",
+        value_delimiter = ',',
+        default_value = "linear"
+    )]
+    y_scale: Vec<Scale>,
 }
 
 impl Cli {
-    pub fn init() -> Result<
-        (DataPointsCollection, XRange, YRange, f64, PathBuf, PathBuf),
-        Error,
-    > {
+    pub fn init() -> Result<Mode, Error> {
         let cli = Cli::parse();
 
-        let data = Self::data(&cli)?;
+        match cli.command {
+            Some(Command::Regressions {
+                new,
+                old,
+                statistic,
+                threshold,
+            }) => {
+                return Ok(Mode::Regressions {
+                    new: Datasets::data(
+                        &new,
+                        ErrorBand::ConfidenceInterval,
+                        None,
+                        statistic,
+                    )?,
+                    old: Datasets::data(
+                        &old,
+                        ErrorBand::ConfidenceInterval,
+                        None,
+                        statistic,
+                    )?,
+                    threshold,
+                });
+            }
+
+            Some(Command::Table { datasets, markup }) => {
+                return Ok(Mode::Table(datasets.load()?, markup));
+            }
+
+            None => {}
+        }
+
+        let data = cli.datasets.load()?;
+
+        Self::check_scale('x', cli.x_min.map(|x| x as f64), &cli.x_scale)?;
+        Self::check_scale('y', cli.y_min, &cli.y_scale)?;
 
-        let x_range = Self::x_range(&data, cli.x_min, cli.x_max);
-        let y_range = Self::y_range(&data, cli.y_min, cli.y_max);
+        let x_range = Self::x_range(&data, cli.x_min, cli.x_max)?;
+        let y_range = Self::y_range(&data, cli.y_min, cli.y_max)?;
 
-        Ok((
+        Ok(Mode::Plot(Box::new(Options {
+            baseline: cli.baseline,
             data,
+            extrapolate: cli.extrapolate,
+            fit_min_input: cli.fit_min_input,
+            output: Output {
+                columns: cli.columns,
+                directory: PathBuf::from(cli.output_directory),
+                dpi: cli.dpi,
+                filename_template: cli.filename_template,
+                format: cli.format,
+                grid: cli.grid,
+                height: cli.height,
+                style: cli.style.style()?,
+                terminal: cli.terminal,
+                width: cli.width,
+            },
+            plots: cli.plots,
             x_range,
+            x_scales: cli.x_scale,
             y_range,
-            cli.log_base,
-            Path::new(&cli.output_directory).join(LINEAR_FILENAME),
-            Path::new(&cli.output_directory).join(LOG_FILENAME),
-        ))
+            y_scales: cli.y_scale,
+        })))
     }
 
-    fn data(cli: &Cli) -> Result<DataPointsCollection, Error> {
-        cli.input
-            .iter()
-            .map(|path| -> Result<DataPoints, Error> {
-                let entries = serde_json::from_reader::<_, Benchmarks>(
-                    BufReader::new(File::open(path)?),
-                )?;
-
-                Ok(DataPoints {
-                    data: entries
-                        .benchmarks
+    /// Check that an axis minimum is positive if any of its scales is
+    /// logarithmic.
+    fn check_scale(
+        axis: char,
+        min: Option<f64>,
+        scales: &[Scale],
+    ) -> Result<(), Error> {
+        match min {
+            Some(min)
+                if min <= 0.0
+                    && scales
                         .iter()
-                        .map(|benchmark| {
-                            const NANOSECONDS_PER_SECOND: f64 = 1e9;
+                        .any(|scale| matches!(scale, Scale::Log(_))) =>
+            {
+                Err(Error::Scale { axis, min })
+            }
 
-                            (
-                                benchmark.input,
-                                benchmark.time / NANOSECONDS_PER_SECOND,
-                            )
-                        })
-                        .collect(),
-
-                    source: entries.name,
-                })
-            })
-            .collect::<Result<Vec<_>, _>>()
+            _ => Ok(()),
+        }
     }
 
+    /// Range of the inputs in `data`, overridden by `x_min` and `x_max`.
     fn x_range(
         data: &DataPointsCollection,
         x_min: Option<Input>,
         x_max: Option<Input>,
-    ) -> XRange {
-        data.iter()
+    ) -> Result<XRange, Error> {
+        let (min, max) = data
+            .iter()
             .flat_map(|s| s.data.iter().map(|(x, _)| *x))
-            .pipe(|x| {
-                x_min.unwrap_or(
-                    x.clone()
-                        .min()
-                        .expect("at least one input entry should exist"),
-                )
-                    ..x_max.unwrap_or(
-                        x.clone()
-                            .max()
-                            .expect("at least one input entry should exist"),
-                    )
-            })
+            .minmax()
+            .into_option()
+            .ok_or(Error::NoData)?;
+
+        let range = x_min.unwrap_or(min)..x_max.unwrap_or(max);
+
+        if (x_min.is_some() || x_max.is_some()) && range.start >= range.end {
+            return Err(Error::Range {
+                axis: 'x',
+                max: range.end as f64,
+                min: range.start as f64,
+            });
+        }
+
+        Ok(range)
     }
 
-    fn y_range(
-        data: &DataPointsCollection,
+    /// Range of the times and their bounds in `data`, overridden by `y_min`
+    /// and `y_max`.
+    pub fn y_range(
+        data: &[DataPoints],
         y_min: Option<Time>,
         y_max: Option<Time>,
-    ) -> YRange {
-        data.iter()
-            .flat_map(|s| s.data.iter().map(|(_, y)| *y))
-            .pipe(|y| {
-                y_min.unwrap_or(
-                    y.clone()
-                        .min_by(|a, b| {
-                            a.partial_cmp(b).expect("time should be comparable")
-                        })
-                        .expect("at least one time entry should exist"),
+    ) -> Result<YRange, Error> {
+        let (min, max) = data
+            .iter()
+            .flat_map(|s| {
+                s.data.iter().map(|(_, y)| *y).chain(
+                    s.bounds
+                        .values()
+                        .flat_map(|(lower, upper)| [*lower, *upper]),
                 )
-                    ..y_max.unwrap_or(
-                        y.max_by(|a, b| {
-                            a.partial_cmp(b).expect("time should be comparable")
+            })
+            .minmax_by(Time::total_cmp)
+            .into_option()
+            .ok_or(Error::NoData)?;
+
+        let range = y_min.unwrap_or(min)..y_max.unwrap_or(max);
+
+        if (y_min.is_some() || y_max.is_some()) && range.start >= range.end {
+            return Err(Error::Range {
+                axis: 'y',
+                max: range.end,
+                min: range.start,
+            });
+        }
+
+        Ok(range)
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the datasets as a table of times, and speedups over the previous
+    /// dataset.
+    Table {
+        #[command(flatten)]
+        datasets: Datasets,
+
+        /// Markup of the table.
+        #[arg(short, long, value_name = "MARKUP", default_value = "asciidoc")]
+        markup: Markup,
+    },
+
+    /// Compare old and new datasets by name and input size, failing on
+    /// significant slowdowns beyond the threshold.
+    Regressions {
+        /// Paths to the new JSON datasets.
+        #[arg(
+            long,
+            value_name = "INPUT",
+            value_hint = FilePath,
+            num_args = 1..,
+            required = true,
+        )]
+        new: Vec<PathBuf>,
+
+        /// Paths to the old JSON datasets.
+        #[arg(
+            long,
+            value_name = "INPUT",
+            value_hint = FilePath,
+            num_args = 1..,
+            required = true,
+        )]
+        old: Vec<PathBuf>,
+
+        /// Statistic of the measured times to compare.
+        #[arg(long, value_name = "STATISTIC", default_value = "mean")]
+        statistic: Statistic,
+
+        /// Slowdown in percent beyond which a significant change counts as a
+        /// regression.
+        #[arg(long, value_name = "PERCENT", default_value = "5")]
+        threshold: f64,
+    },
+}
+
+#[derive(Args)]
+struct Datasets {
+    /// Use datasets benchmarked on different hardware or software.
+    #[arg(long)]
+    allow_incompatible: bool,
+
+    /// Band drawn around each line to show the uncertainty of its times.
+    #[arg(long, value_name = "BAND", default_value = "confidence-interval")]
+    error_band: ErrorBand,
+
+    /// Metadata to group datasets from several machines by, coloring and
+    /// labelling them by group and comparing speedups within each group.
+    /// Implies `--allow-incompatible`.
+    #[arg(long, value_name = "KEY")]
+    group_by: Option<Key>,
+
+    /// Path to the JSON datasets, criterion output directories such as
+    /// `target/criterion`, or `cargo criterion --message-format json` output.
+    #[arg(
+        value_name = "INPUT",
+        value_hint = AnyPath,
+        required = true,
+    )]
+    input: Vec<PathBuf>,
+
+    /// Statistic of the measured times to use.
+    #[arg(long, value_name = "STATISTIC", default_value = "mean")]
+    statistic: Statistic,
+}
+
+impl Datasets {
+    fn load(&self) -> Result<DataPointsCollection, Error> {
+        let data = Self::data(
+            &self.input,
+            self.error_band,
+            self.group_by,
+            self.statistic,
+        )?;
+
+        if !self.allow_incompatible && self.group_by.is_none() {
+            Self::check_compatibility(&data)?;
+        }
+
+        Ok(data)
+    }
+
+    /// Read the datasets at `path`, which is either a JSON dataset, a criterion
+    /// output directory, or a stream of `cargo criterion` JSON messages.
+    fn read(path: &Path) -> Result<Vec<Benchmarks>, Error> {
+        if path.is_dir() {
+            return criterion::directory(path);
+        }
+
+        let contents = error::read(path)?;
+
+        match error::parse::<Benchmarks>(path, &contents) {
+            Ok(entries) => Ok(vec![entries]),
+            Err(error) => {
+                criterion::stream(path, &contents).unwrap_or(Err(error))
+            }
+        }
+    }
+
+    fn check_compatibility(data: &DataPointsCollection) -> Result<(), Error> {
+        if let Some(first) = data.first()
+            && let Some(incompatible) = data.iter().find(|series| {
+                !compatible(series.metadata.as_ref(), first.metadata.as_ref())
+            })
+        {
+            return Err(Error::Incompatible {
+                first: first.source.clone(),
+                first_path: first.path.clone(),
+                other: incompatible.source.clone(),
+                other_path: incompatible.path.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Read the datasets in `input`, skipping those without valid benchmarks
+    /// with a warning.
+    fn data(
+        input: &[PathBuf],
+        error_band: ErrorBand,
+        group_by: Option<Key>,
+        statistic: Statistic,
+    ) -> Result<DataPointsCollection, Error> {
+        let mut data = Vec::new();
+
+        for path in input {
+            for entries in Self::read(path)? {
+                match Self::data_points(
+                    entries, path, error_band, group_by, statistic,
+                ) {
+                    Ok(series) => data.push(series),
+
+                    Err((source, reason)) => eprintln!(
+                        "warning: skipping {source} from {}: {reason}",
+                        path.display()
+                    ),
+                }
+            }
+        }
+
+        if data.is_empty() {
+            return Err(Error::NoData);
+        }
+
+        Ok(data)
+    }
+
+    /// Convert the benchmarks of a dataset to seconds, unless it has none or
+    /// one lacks a finite `statistic`, in which case the dataset's name and
+    /// the reason are returned.
+    fn data_points(
+        entries: Benchmarks,
+        path: &Path,
+        error_band: ErrorBand,
+        group_by: Option<Key>,
+        statistic: Statistic,
+    ) -> Result<DataPoints, (String, String)> {
+        if entries.benchmarks.is_empty() {
+            return Err((entries.name, "no benchmarks".to_string()));
+        }
+
+        let estimates = entries
+            .benchmarks
+            .iter()
+            .map(|benchmark| match statistic.estimate(benchmark) {
+                Some(estimate) if estimate.estimate.is_finite() => Ok(estimate),
+
+                Some(_) => Err(format!(
+                    "non-finite {statistic} of input {}",
+                    benchmark.input
+                )),
+
+                None => Err(format!(
+                    "{statistic} of input {} missing",
+                    benchmark.input
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>();
+
+        let estimates = match estimates {
+            Ok(estimates) => estimates,
+            Err(reason) => return Err((entries.name, reason)),
+        };
+
+        Ok(DataPoints {
+            bounds: entries
+                .benchmarks
+                .iter()
+                .zip(&estimates)
+                .filter_map(|(benchmark, estimate)| {
+                    error_band
+                        .bounds(benchmark, estimate)
+                        .filter(|(lower, upper)| {
+                            lower.is_finite() && upper.is_finite()
                         })
-                        .expect("at least one time entry should exist"),
+                        .map(|(lower, upper)| {
+                            (
+                                benchmark.input,
+                                (
+                                    lower / NANOSECONDS_PER_SECOND,
+                                    upper / NANOSECONDS_PER_SECOND,
+                                ),
+                            )
+                        })
+                })
+                .collect(),
+
+            bytes: entries
+                .benchmarks
+                .iter()
+                .filter_map(|benchmark| {
+                    benchmark.bytes.map(|bytes| (benchmark.input, bytes))
+                })
+                .collect(),
+
+            data: entries
+                .benchmarks
+                .iter()
+                .zip(&estimates)
+                .map(|(benchmark, estimate)| {
+                    (
+                        benchmark.input,
+                        estimate.estimate / NANOSECONDS_PER_SECOND,
                     )
-            })
+                })
+                .collect(),
+
+            fitted: Vec::new(),
+
+            group: group_by.map(|key| {
+                entries
+                    .metadata
+                    .as_ref()
+                    .and_then(|metadata| key.value(metadata))
+                    .unwrap_or_else(|| UNKNOWN_GROUP.to_string())
+            }),
+
+            instructions: entries
+                .benchmarks
+                .iter()
+                .filter_map(|benchmark| {
+                    benchmark
+                        .counters?
+                        .instructions
+                        .map(|instructions| (benchmark.input, instructions))
+                })
+                .collect(),
+
+            metadata: entries.metadata,
+            path: path.to_path_buf(),
+            source: entries.name,
+        })
+    }
+}
+
+#[derive(Args)]
+#[command(next_help_heading = "Appearance")]
+struct StyleArgs {
+    /// Font size of the caption and bar labels in points at 96 DPI.
+    #[arg(long, value_name = "POINTS")]
+    caption_font_size: Option<u32>,
+
+    /// Color of a dataset as `NAME=#RRGGBB`.
+    #[arg(long, value_name = "NAME=COLOR", value_parser = named::<Color>)]
+    color: Vec<(String, Color)>,
+
+    /// TOML file configuring the appearance, overridden by its flags.
+    #[arg(long, value_name = "PATH", value_hint = FilePath)]
+    config: Option<PathBuf>,
+
+    /// Font size of the axis descriptions, tick labels, and legend in points
+    /// at 96 DPI.
+    #[arg(long, value_name = "POINTS")]
+    label_font_size: Option<u32>,
+
+    /// Position of the legend.
+    #[arg(long, value_name = "POSITION")]
+    legend: Option<Legend>,
+
+    /// Line style of a dataset as `NAME=STYLE`, with `STYLE` being `solid`,
+    /// `dashed`, or `dotted`.
+    #[arg(
+        long,
+        value_name = "NAME=STYLE",
+        value_parser = named::<LineStyle>,
+    )]
+    line_style: Vec<(String, LineStyle)>,
+
+    /// Marker of a dataset's data points as `NAME=MARKER`, with `MARKER` being
+    /// `none`, `circle`, `cross`, `square`, or `triangle`.
+    #[arg(long, value_name = "NAME=MARKER", value_parser = named::<Marker>)]
+    marker: Vec<(String, Marker)>,
+
+    /// Color theme.
+    #[arg(long, value_name = "THEME")]
+    theme: Option<Theme>,
+
+    /// Title of the plots.
+    #[arg(long, value_name = "TITLE")]
+    title: Option<String>,
+
+    /// Font size of the title in points at 96 DPI.
+    #[arg(long, value_name = "POINTS")]
+    title_font_size: Option<u32>,
+
+    /// Description of the x-axis, overriding the default.
+    #[arg(long, value_name = "LABEL")]
+    x_label: Option<String>,
+
+    /// Description of the y-axis, overriding the default of each plot.
+    #[arg(long, value_name = "LABEL")]
+    y_label: Option<String>,
+}
+
+impl StyleArgs {
+    /// Read the configuration file, if any, and apply the flags on top.
+    fn style(self) -> Result<Style, Error> {
+        let mut style = match &self.config {
+            Some(path) => error::parse_toml(path, &error::read(path)?)?,
+            None => Style::default(),
+        };
+
+        let font_sizes = &mut style.font_sizes;
+
+        font_sizes.caption =
+            self.caption_font_size.unwrap_or(font_sizes.caption);
+        font_sizes.label = self.label_font_size.unwrap_or(font_sizes.label);
+        font_sizes.title = self.title_font_size.unwrap_or(font_sizes.title);
+
+        style.legend = self.legend.unwrap_or(style.legend);
+        style.theme = self.theme.unwrap_or(style.theme);
+        style.title = self.title.or(style.title);
+        style.x_label = self.x_label.or(style.x_label);
+        style.y_label = self.y_label.or(style.y_label);
+
+        for (name, color) in self.color {
+            style.series.entry(name).or_default().color = Some(color);
+        }
+
+        for (name, line) in self.line_style {
+            style.series.entry(name).or_default().line = line;
+        }
+
+        for (name, marker) in self.marker {
+            style.series.entry(name).or_default().marker = marker;
+        }
+
+        Ok(style)
+    }
+}
+
+/// Parse a resolution of at least `MIN_DPI`.
+fn dpi(argument: &str) -> Result<f64, String> {
+    let dpi = argument
+        .parse::<f64>()
+        .map_err(|error| format!("{argument:?}: {error}"))?;
+
+    if dpi >= MIN_DPI && dpi.is_finite() {
+        Ok(dpi)
+    } else {
+        Err(format!(
+            "{dpi} is not a finite resolution of at least {MIN_DPI}"
+        ))
     }
 }
 
+/// Parse a `NAME=VALUE` argument.
+fn named<T>(argument: &str) -> Result<(String, T), String>
+where
+    T: FromStr<Err = String>,
+{
+    let (name, value) = argument
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected NAME=VALUE, got {argument:?}"))?;
+
+    Ok((name.to_string(), value.parse()?))
+}
+
 pub struct DataPoints {
+    /// Lower and upper bound of the error band at each input, where known.
+    pub bounds: HashMap<Input, (Time, Time)>,
+
+    pub bytes: HashMap<Input, Bytes>,
     pub data: Vec<(Input, Time)>,
+
+    /// Fitted curve drawn dashed alongside the data, if any.
+    pub fitted: Vec<(Input, Time)>,
+
+    /// Metadata value the dataset is grouped by, if grouping.
+    pub group: Option<String>,
+
+    /// Instructions of a single run at each input, where counted.
+    pub instructions: HashMap<Input, Count>,
+
+    pub metadata: Option<Metadata>,
+
+    /// File or directory the dataset was read from.
+    pub path: PathBuf,
+
     pub source: String,
 }
+
+impl DataPoints {
+    /// Dataset named `source` with values `data`, without an error band or
+    /// metadata.
+    #[cfg(test)]
+    pub fn new(source: &str, data: Vec<(Input, Time)>) -> Self {
+        Self {
+            bounds: HashMap::new(),
+            bytes: HashMap::new(),
+            data,
+            fitted: Vec::new(),
+            group: None,
+            instructions: HashMap::new(),
+            metadata: None,
+            path: format!("{source}.json").into(),
+            source: source.to_string(),
+        }
+    }
+
+    /// Copy of the dataset with its values and error band replaced by `data`
+    /// and `bounds`, without a fitted curve.
+    pub fn with_data(
+        &self,
+        bounds: HashMap<Input, (Time, Time)>,
+        data: Vec<(Input, Time)>,
+    ) -> Self {
+        Self {
+            bounds,
+            bytes: self.bytes.clone(),
+            data,
+            fitted: Vec::new(),
+            group: self.group.clone(),
+            instructions: self.instructions.clone(),
+            metadata: self.metadata.clone(),
+            path: self.path.clone(),
+            source: self.source.clone(),
+        }
+    }
+
+    /// Name of the dataset, followed by its group if grouping.
+    pub fn label(&self) -> String {
+        match &self.group {
+            Some(group) => format!("{} ({group})", self.source),
+            None => self.source.clone(),
+        }
+    }
+}
+
+pub enum Mode {
+    Plot(Box<Options>),
+
+    Regressions {
+        new: DataPointsCollection,
+        old: DataPointsCollection,
+        threshold: f64,
+    },
+
+    Table(DataPointsCollection, Markup),
+}
+
+pub struct Options {
+    pub baseline: Option<String>,
+    pub data: DataPointsCollection,
+    pub extrapolate: Input,
+    pub fit_min_input: Input,
+    pub output: Output,
+    pub plots: Vec<Plot>,
+    pub x_range: XRange,
+    pub x_scales: Vec<Scale>,
+    pub y_range: YRange,
+    pub y_scales: Vec<Scale>,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Plot {
+    /// Times of the datasets by input size.
+    Time,
+
+    /// Rows per second of the datasets by input size.
+    ThroughputRows,
+
+    /// Megabytes per second of the datasets by input size.
+    ThroughputBytes,
+
+    /// Instructions per row of the datasets by input size.
+    InstructionsPerRow,
+
+    /// Speedups of the datasets over the baseline by input size.
+    Speedup,
+
+    /// Speedups of the datasets over the baseline at their largest common input
+    /// size.
+    SpeedupSummary,
+
+    /// Times of the datasets with the curves of their best fitting models,
+    /// printing the models and their extrapolated times.
+    Fit,
+
+    /// Interactive HTML report of the times.
+    Report,
+}
+
+#[derive(Clone, Copy)]
+pub enum Scale {
+    Linear,
+    Log(f64),
+}
+
+impl Display for Scale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Linear => write!(f, "linear"),
+            Self::Log(base) => write!(f, "log{base}"),
+        }
+    }
+}
+
+impl FromStr for Scale {
+    type Err = String;
+
+    fn from_str(scale: &str) -> Result<Self, Self::Err> {
+        const DEFAULT_LOG_BASE: f64 = 10.0;
+
+        match scale {
+            "linear" => Ok(Self::Linear),
+            "log" => Ok(Self::Log(DEFAULT_LOG_BASE)),
+
+            _ => scale
+                .strip_prefix("log")
+                .and_then(|base| base.parse::<f64>().ok())
+                .filter(|base| *base > 1.0)
+                .map(Self::Log)
+                .ok_or_else(|| {
+                    format!(
+                        "invalid scale: {scale}: expected linear, log, or \
+                        log<BASE> with a base greater than 1"
+                    )
+                }),
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ErrorBand {
+    /// Confidence interval of the plotted statistic.
+    ConfidenceInterval,
+
+    /// No band.
+    None,
+
+    /// One standard deviation around the plotted statistic.
+    StandardDeviation,
+}
+
+impl ErrorBand {
+    /// Bounds of the band around `estimate`, unknown for older datasets.
+    ///
+    /// A standard deviation as large as the estimate is omitted, since its
+    /// non-positive lower bound has no inverse for throughputs and speedups,
+    /// nor a logarithm.
+    fn bounds(
+        self,
+        benchmark: &Benchmark,
+        estimate: &Estimate,
+    ) -> Option<(Time, Time)> {
+        match self {
+            Self::ConfidenceInterval
+                if benchmark.mean.is_some() || benchmark.median.is_some() =>
+            {
+                Some((estimate.lower_bound, estimate.upper_bound))
+            }
+
+            Self::ConfidenceInterval | Self::None => None,
+
+            Self::StandardDeviation => benchmark
+                .std_dev
+                .filter(|std_dev| *std_dev < estimate.estimate)
+                .map(|std_dev| {
+                    (estimate.estimate - std_dev, estimate.estimate + std_dev)
+                }),
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Statistic {
+    Mean,
+    Median,
+}
+
+impl Statistic {
+    /// Estimate of the statistic, falling back to the mean time of older
+    /// datasets without confidence intervals.
+    fn estimate(self, benchmark: &Benchmark) -> Option<Estimate> {
+        match self {
+            Self::Mean => benchmark.mean.or(Some(Estimate {
+                estimate: benchmark.time,
+                lower_bound: benchmark.time,
+                upper_bound: benchmark.time,
+            })),
+
+            Self::Median => benchmark.median,
+        }
+    }
+}
+
+impl Display for Statistic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Mean => "mean",
+            Self::Median => "median",
+        }
+        .fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use plotters::style::RGBColor;
+
+    use std::{
+        fs,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use crate::style::FontSizes;
+
+    #[derive(Parser)]
+    struct StyleCli {
+        #[command(flatten)]
+        style: StyleArgs,
+    }
+
+    /// Style configured by the TOML `config`, if any, and the flags `args`.
+    fn style(config: Option<&str>, args: &[&str]) -> Result<Style, Error> {
+        // Tests run in parallel, so each configuration gets its own file.
+        static CONFIGS: AtomicUsize = AtomicUsize::new(0);
+
+        let path = std::env::temp_dir().join(format!(
+            "plot-style-{}-{}.toml",
+            std::process::id(),
+            CONFIGS.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let config = config.map(|config| {
+            fs::write(&path, config).expect("config should be writable");
+
+            ["--config", path.to_str().expect("path should be UTF-8")]
+        });
+
+        let style = StyleCli::try_parse_from(
+            ["plot"].iter().chain(config.iter().flatten()).chain(args),
+        )
+        .expect("flags should be valid")
+        .style
+        .style();
+
+        if config.is_some() {
+            fs::remove_file(&path).expect("config should be removable");
+        }
+
+        style
+    }
+
+    fn benchmark(time: Time, std_dev: Time) -> Benchmark {
+        Benchmark {
+            bytes: None,
+            counters: None,
+            input: 1,
+            mean: None,
+            median: None,
+            std_dev: Some(std_dev),
+            time,
+        }
+    }
+
+    fn estimate(time: Time) -> Estimate {
+        Estimate {
+            estimate: time,
+            lower_bound: time,
+            upper_bound: time,
+        }
+    }
+
+    #[test]
+    fn colors_are_named() {
+        let (name, color) = named::<Color>("v01_base=#ff8000")
+            .expect("named color should be valid");
+
+        assert_eq!(name, "v01_base");
+        assert_eq!(color.0, RGBColor(0xff, 0x80, 0x00));
+
+        for argument in ["v01_base", "v01_base=", "v01_base=ff8000"] {
+            assert!(
+                named::<Color>(argument).is_err(),
+                "{argument:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn config_is_read() {
+        let style = style(
+            Some(
+                r##"
+                legend = "upper-left"
+                theme = "dark"
+                title = "Rows"
+
+                [font-sizes]
+                title = 30
+
+                [series.v01_base]
+                color = "#ff8000"
+                line = "dashed"
+                "##,
+            ),
+            &[],
+        )
+        .expect("config should be valid");
+
+        assert!(matches!(style.legend, Legend::UpperLeft));
+        assert!(matches!(style.theme, Theme::Dark));
+        assert_eq!(style.title.as_deref(), Some("Rows"));
+        assert_eq!(style.font_sizes.caption, FontSizes::default().caption);
+        assert_eq!(style.font_sizes.title, 30);
+
+        let series = style.series["v01_base"];
+
+        assert_eq!(
+            series.color.map(|color| color.0),
+            Some(RGBColor(0xff, 0x80, 0x00))
+        );
+        assert!(matches!(series.line, LineStyle::Dashed));
+        assert!(matches!(series.marker, Marker::None));
+    }
+
+    #[test]
+    fn flags_override_config() {
+        let style = style(
+            Some(
+                r##"
+                theme = "dark"
+                title = "Rows"
+                x-label = "Lines"
+
+                [font-sizes]
+                title = 30
+
+                [series.v01_base]
+                color = "#ff8000"
+                line = "dashed"
+                "##,
+            ),
+            &[
+                "--title=Speed",
+                "--title-font-size=24",
+                "--color=v01_base=#0000ff",
+                "--marker=v01_base=circle",
+            ],
+        )
+        .expect("config should be valid");
+
+        assert!(matches!(style.theme, Theme::Dark));
+        assert_eq!(style.title.as_deref(), Some("Speed"));
+        assert_eq!(style.x_label.as_deref(), Some("Lines"));
+        assert_eq!(style.font_sizes.title, 24);
+
+        let series = style.series["v01_base"];
+
+        assert_eq!(
+            series.color.map(|color| color.0),
+            Some(RGBColor(0, 0, 0xff))
+        );
+        assert!(matches!(series.line, LineStyle::Dashed));
+        assert!(matches!(series.marker, Marker::Circle));
+    }
+
+    #[test]
+    fn flags_apply_without_config() {
+        let style = style(None, &["--legend=none", "--line-style=v02=dotted"])
+            .expect("flags should be valid");
+
+        assert!(matches!(style.legend, Legend::None));
+        assert!(matches!(style.theme, Theme::Light));
+        assert!(matches!(style.series["v02"].line, LineStyle::Dotted));
+    }
+
+    #[test]
+    fn invalid_config_is_rejected() {
+        for config in [
+            "colour = \"#ff8000\"",
+            "theme = \"sepia\"",
+            "[series.v01_base]\ncolor = \"orange\"",
+            "[font-sizes]\ntitle = -1",
+        ] {
+            assert!(
+                style(Some(config), &[]).is_err(),
+                "{config:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_flags_are_rejected() {
+        for args in [
+            ["--color", "v01_base=orange"],
+            ["--legend", "left"],
+            ["--line-style", "v01_base"],
+            ["--title-font-size", "large"],
+        ] {
+            assert!(
+                StyleCli::try_parse_from(["plot"].into_iter().chain(args))
+                    .is_err(),
+                "{args:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn scales_are_parsed() {
+        assert!(matches!("linear".parse(), Ok(Scale::Linear)));
+        assert!(matches!("log".parse(), Ok(Scale::Log(10.0))));
+        assert!(matches!("log2".parse(), Ok(Scale::Log(2.0))));
+        assert!(matches!("log1.5".parse(), Ok(Scale::Log(1.5))));
+
+        for scale in ["", "lin", "log1", "log0.5", "log-2", "logx", "log 2"] {
+            assert!(
+                scale.parse::<Scale>().is_err(),
+                "{scale:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn scales_round_trip() {
+        for scale in ["linear", "log10", "log2"] {
+            assert_eq!(
+                scale
+                    .parse::<Scale>()
+                    .expect("scale should be valid")
+                    .to_string(),
+                scale
+            );
+        }
+    }
+
+    #[test]
+    fn standard_deviation_band_surrounds_estimate() {
+        assert_eq!(
+            ErrorBand::StandardDeviation
+                .bounds(&benchmark(4.0, 1.0), &estimate(4.0)),
+            Some((3.0, 5.0))
+        );
+    }
+
+    #[test]
+    fn standard_deviation_band_stays_positive() {
+        for std_dev in [4.0, 5.0] {
+            assert_eq!(
+                ErrorBand::StandardDeviation
+                    .bounds(&benchmark(4.0, std_dev), &estimate(4.0)),
+                None
+            );
+        }
+    }
+}
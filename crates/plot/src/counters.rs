@@ -0,0 +1,25 @@
+//! Hardware performance counters of datasets, relative to the input size.
+
+use crate::cli::DataPoints;
+
+/// Convert each instruction count to instructions per row, skipping inputs
+/// without one and empty inputs.
+pub fn instructions_per_row(data: &[DataPoints]) -> Vec<DataPoints> {
+    data.iter()
+        .map(|series| {
+            series.with_data(
+                Default::default(),
+                series
+                    .data
+                    .iter()
+                    .filter(|(x, _)| *x > 0)
+                    .filter_map(|(x, _)| {
+                        series.instructions.get(x).map(|instructions| {
+                            (*x, *instructions as f64 / *x as f64)
+                        })
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,402 @@
+//! Datasets ingested from plain criterion output, either the estimates saved
+//! under `target/criterion` by `cargo bench`, or the JSON messages printed by
+//! `cargo criterion --message-format json`.
+
+use serde::{Deserialize, de::IgnoredAny};
+
+use tap::Pipe;
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use criterion_messages::{Message, std_dev};
+
+use crate::{
+    benchmarks::{Benchmark, Benchmarks, Bytes, Estimate, Input, Time},
+    error::{self, Error},
+};
+
+const BENCHMARK_FILENAME: &str = "benchmark.json";
+const ESTIMATES_FILENAME: &str = "estimates.json";
+
+/// Directory of the estimates of the latest run of a benchmark.
+const NEW_DIRECTORY: &str = "new";
+
+#[derive(Deserialize)]
+struct BenchmarkId {
+    full_id: String,
+    function_id: Option<String>,
+    group_id: String,
+    throughput: Option<Throughput>,
+    value_str: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ConfidenceInterval {
+    lower_bound: Time,
+    upper_bound: Time,
+}
+
+#[derive(Deserialize)]
+struct Estimates {
+    mean: PointEstimate,
+    median: PointEstimate,
+    std_dev: PointEstimate,
+}
+
+#[derive(Deserialize)]
+struct PointEstimate {
+    confidence_interval: ConfidenceInterval,
+    point_estimate: Time,
+}
+
+impl From<PointEstimate> for Estimate {
+    fn from(estimate: PointEstimate) -> Self {
+        Self {
+            estimate: estimate.point_estimate,
+            lower_bound: estimate.confidence_interval.lower_bound,
+            upper_bound: estimate.confidence_interval.upper_bound,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+enum Throughput {
+    Bytes(Bytes),
+    BytesDecimal(Bytes),
+    Elements(IgnoredAny),
+}
+
+/// Datasets of the benchmarks saved anywhere below `path`, one per iteration,
+/// skipping benchmarks whose ID has no input size with a warning.
+pub fn directory(path: &Path) -> Result<Vec<Benchmarks>, Error> {
+    let mut directories = Vec::new();
+    find(path, &mut directories)?;
+
+    directories
+        .iter()
+        .map(|directory| {
+            let read = |filename| {
+                let path = directory.join(filename);
+                error::read(&path).map(|contents| (path, contents))
+            };
+
+            let (path, contents) = read(BENCHMARK_FILENAME)?;
+            let id = error::parse::<BenchmarkId>(&path, &contents)?;
+
+            let (path, contents) = read(ESTIMATES_FILENAME)?;
+            let estimates = error::parse::<Estimates>(&path, &contents)?;
+
+            let parameter = id
+                .value_str
+                .as_deref()
+                .or(id.function_id.as_deref())
+                .unwrap_or_default();
+
+            let Ok(input) = parameter.parse::<Input>() else {
+                let error = Error::Input {
+                    id: id.full_id,
+                    path: directory.to_path_buf(),
+                };
+
+                eprintln!("warning: skipping {error}");
+                return Ok(None);
+            };
+
+            let mean = Estimate::from(estimates.mean);
+
+            Ok(Some((
+                id.group_id,
+                Benchmark {
+                    bytes: match id.throughput {
+                        Some(
+                            Throughput::Bytes(bytes)
+                            | Throughput::BytesDecimal(bytes),
+                        ) => Some(bytes),
+
+                        Some(Throughput::Elements(_)) | None => None,
+                    },
+
+                    counters: None,
+                    input,
+                    mean: Some(mean),
+                    median: Some(estimates.median.into()),
+                    std_dev: Some(estimates.std_dev.point_estimate),
+                    time: mean.estimate,
+                },
+            )))
+        })
+        .filter_map(Result::transpose)
+        .collect::<Result<Vec<_>, Error>>()
+        .map(group)
+}
+
+/// Datasets of the completed benchmarks in the stream of JSON messages at
+/// `path`, one per iteration, with benchmark IDs formatted as
+/// `<iteration>/<input name>/<rows>`, unless `contents` is no such stream.
+pub fn stream(
+    path: &Path,
+    contents: &str,
+) -> Option<Result<Vec<Benchmarks>, Error>> {
+    let messages = serde_json::Deserializer::from_str(contents)
+        .into_iter::<Message>()
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+
+    messages
+        .into_iter()
+        .filter_map(|message| match message {
+            Message::BenchmarkComplete {
+                id,
+                iteration_count,
+                mean,
+                measured_values,
+                median,
+                throughput,
+            } => Some(
+                id.split_once('/')
+                    .zip(id.rsplit_once('/'))
+                    .and_then(|((iteration, _), (_, rows))| {
+                        Some((iteration.to_string(), rows.parse().ok()?))
+                    })
+                    .ok_or_else(|| Error::Input {
+                        id: id.clone(),
+                        path: path.to_path_buf(),
+                    })
+                    .map(|(iteration, input)| {
+                        (
+                            iteration,
+                            Benchmark {
+                                bytes: throughput
+                                    .iter()
+                                    .find(|throughput| {
+                                        throughput.unit == "bytes"
+                                    })
+                                    .map(|throughput| throughput.per_iteration),
+
+                                counters: None,
+                                input,
+                                mean: Some(mean),
+                                median: Some(median),
+                                std_dev: Some(std_dev(
+                                    &measured_values,
+                                    &iteration_count,
+                                )),
+                                time: mean.estimate,
+                            },
+                        )
+                    }),
+            ),
+
+            Message::Other => None,
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(group)
+        .pipe(Some)
+}
+
+/// Collect the directories of the latest estimates below `path`.
+fn find(path: &Path, directories: &mut Vec<PathBuf>) -> Result<(), Error> {
+    let io = |source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    for entry in fs::read_dir(path).map_err(io)? {
+        let path = entry.map_err(io)?.path();
+
+        if !path.is_dir() {
+            continue;
+        }
+
+        if path.file_name().is_some_and(|name| name == NEW_DIRECTORY)
+            && path.join(BENCHMARK_FILENAME).is_file()
+        {
+            directories.push(path);
+        } else {
+            find(&path, directories)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Group benchmarks into a dataset per iteration, ordered by input size.
+fn group(benchmarks: Vec<(String, Benchmark)>) -> Vec<Benchmarks> {
+    benchmarks
+        .into_iter()
+        .fold(
+            BTreeMap::<_, Vec<_>>::new(),
+            |mut groups, (name, benchmark)| {
+                groups.entry(name).or_default().push(benchmark);
+                groups
+            },
+        )
+        .into_iter()
+        .map(|(name, mut benchmarks)| {
+            benchmarks.sort_by_key(|benchmark| benchmark.input);
+
+            Benchmarks {
+                benchmarks,
+                metadata: None,
+                name,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STREAM: &str = r#"
+        {"reason":"group-complete","group_name":"v01_base"}
+        {"reason":"benchmark-complete","id":"v01_base/measurements/20",
+         "iteration_count":[1,2],"measured_values":[10.0,30.0],
+         "mean":{"estimate":12.5,"lower_bound":10.0,"upper_bound":15.0},
+         "median":{"estimate":12.5,"lower_bound":10.0,"upper_bound":15.0},
+         "throughput":[{"per_iteration":200,"unit":"bytes"}]}
+        {"reason":"benchmark-complete","id":"v01_base/measurements/10",
+         "iteration_count":[1],"measured_values":[5.0],
+         "mean":{"estimate":5.0,"lower_bound":5.0,"upper_bound":5.0},
+         "median":{"estimate":5.0,"lower_bound":5.0,"upper_bound":5.0}}
+    "#;
+
+    fn estimate(value: Time) -> String {
+        format!(
+            r#"{{"confidence_interval":{{"lower_bound":{},"upper_bound":{}}},
+                "point_estimate":{value}}}"#,
+            value - 1.0,
+            value + 1.0
+        )
+    }
+
+    #[test]
+    fn stream_groups_completed_benchmarks() {
+        let datasets = stream(Path::new("stream.json"), STREAM)
+            .expect("contents should be a stream")
+            .expect("stream should be valid");
+
+        assert_eq!(datasets.len(), 1);
+        assert_eq!(datasets[0].name, "v01_base");
+
+        let [first, second] = datasets[0].benchmarks.as_slice() else {
+            panic!("dataset should have two benchmarks");
+        };
+
+        assert_eq!((first.input, first.bytes), (10, None));
+        assert_eq!(first.std_dev, Some(0.0));
+        assert_eq!((second.input, second.bytes), (20, Some(200)));
+        assert_eq!(second.time, 12.5);
+        assert_eq!(second.std_dev, Some(12.5_f64.sqrt()));
+    }
+
+    #[test]
+    fn stream_rejects_other_json() {
+        assert!(
+            stream(Path::new("v01_base.json"), r#"{"name":"v01_base"}"#)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn stream_rejects_ids_without_rows() {
+        let contents = r#"{"reason":"benchmark-complete","id":"v01_base",
+            "iteration_count":[1],"measured_values":[1.0],
+            "mean":{"estimate":1.0,"lower_bound":1.0,"upper_bound":1.0},
+            "median":{"estimate":1.0,"lower_bound":1.0,"upper_bound":1.0}}"#;
+
+        assert!(matches!(
+            stream(Path::new("stream.json"), contents),
+            Some(Err(Error::Input { .. }))
+        ));
+    }
+
+    /// Save the estimates of a benchmark of `value_str` below `root`, like
+    /// `cargo bench` does.
+    fn save(root: &Path, value_str: &str, throughput: &str, time: Time) {
+        let benchmark = root.join(format!("v01_base/measurements/{value_str}"));
+
+        // Estimates of earlier runs are kept beside the latest ones.
+        fs::create_dir_all(benchmark.join("base"))
+            .expect("fixture directory should be creatable");
+
+        let benchmark = benchmark.join(NEW_DIRECTORY);
+
+        fs::create_dir_all(&benchmark)
+            .expect("fixture directory should be creatable");
+
+        fs::write(
+            benchmark.join(BENCHMARK_FILENAME),
+            format!(
+                r#"{{"full_id":"v01_base/measurements/{value_str}",
+                    "function_id":"measurements","group_id":"v01_base",
+                    "throughput":{throughput},"value_str":"{value_str}"}}"#
+            ),
+        )
+        .expect("fixture file should be writable");
+
+        fs::write(
+            benchmark.join(ESTIMATES_FILENAME),
+            format!(
+                r#"{{"mean":{},"median":{},"std_dev":{}}}"#,
+                estimate(time),
+                estimate(time),
+                estimate(2.0)
+            ),
+        )
+        .expect("fixture file should be writable");
+    }
+
+    #[test]
+    fn directory_reads_latest_estimates() {
+        let root = std::env::temp_dir()
+            .join(format!("plot-criterion-{}", std::process::id()));
+
+        save(&root, "20", r#"{"Bytes":200}"#, 20.0);
+        save(&root, "10", "null", 10.0);
+
+        let datasets = directory(&root);
+        fs::remove_dir_all(&root).expect("fixture should be removable");
+        let datasets = datasets.expect("directory should be valid");
+
+        assert_eq!(datasets.len(), 1);
+        assert_eq!(datasets[0].name, "v01_base");
+
+        let [first, second] = datasets[0].benchmarks.as_slice() else {
+            panic!("dataset should have two benchmarks");
+        };
+
+        assert_eq!((first.input, first.bytes, first.time), (10, None, 10.0));
+        assert_eq!(first.std_dev, Some(2.0));
+
+        assert_eq!(
+            (second.input, second.bytes, second.time),
+            (20, Some(200), 20.0)
+        );
+
+        assert_eq!(second.mean.map(|mean| mean.lower_bound), Some(19.0));
+    }
+
+    #[test]
+    fn directory_skips_ids_without_rows() {
+        let root = std::env::temp_dir()
+            .join(format!("plot-criterion-skip-{}", std::process::id()));
+
+        save(&root, "10", "null", 10.0);
+        save(&root, "all", "null", 20.0);
+
+        let datasets = directory(&root);
+        fs::remove_dir_all(&root).expect("fixture should be removable");
+        let datasets = datasets.expect("directory should be valid");
+
+        let [benchmark] = datasets[0].benchmarks.as_slice() else {
+            panic!("dataset should have one benchmark");
+        };
+
+        assert_eq!((benchmark.input, benchmark.time), (10, 10.0));
+    }
+}
@@ -0,0 +1,155 @@
+//! Errors of reading the datasets and of validating the plot options, naming
+//! the offending file and, for invalid JSON, the location within it.
+
+use serde::de::DeserializeOwned;
+
+use std::{
+    error,
+    fmt::{self, Display},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug)]
+pub enum Error {
+    /// Datasets benchmarked on different hardware or software.
+    Incompatible {
+        first: String,
+        first_path: PathBuf,
+        other: String,
+        other_path: PathBuf,
+    },
+
+    /// Benchmark of criterion output whose ID has no input size.
+    Input {
+        id: String,
+        path: PathBuf,
+    },
+
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+
+    /// JSON not matching the expected structure, located by the path of the
+    /// offending value and its line and column.
+    Json {
+        path: PathBuf,
+        source: serde_path_to_error::Error<serde_json::Error>,
+    },
+
+    /// No dataset with valid benchmarks.
+    NoData,
+
+    /// Axis range whose minimum is not below its maximum.
+    Range {
+        axis: char,
+        max: f64,
+        min: f64,
+    },
+
+    /// Logarithmic axis with a non-positive minimum.
+    Scale {
+        axis: char,
+        min: f64,
+    },
+
+    /// Configuration file not matching the expected structure.
+    Toml {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Incompatible {
+                first,
+                first_path,
+                other,
+                other_path,
+            } => write!(
+                f,
+                "benchmark metadata mismatch between {first} from {} and \
+                {other} from {}",
+                first_path.display(),
+                other_path.display()
+            ),
+
+            Self::Input { id, path } => write!(
+                f,
+                "{}: no input size in benchmark ID {id:?}",
+                path.display()
+            ),
+
+            Self::Io { path, source } => {
+                write!(f, "{}: {source}", path.display())
+            }
+
+            Self::Json { path, source } => {
+                write!(f, "{}: {source}", path.display())
+            }
+
+            Self::NoData => write!(f, "no dataset with valid benchmarks"),
+
+            Self::Range { axis, max, min } => write!(
+                f,
+                "{axis}-axis minimum {min} should be less than its maximum {max}"
+            ),
+
+            Self::Scale { axis, min } => write!(
+                f,
+                "{axis}-axis minimum {min} should be positive on a \
+                logarithmic scale"
+            ),
+
+            Self::Toml { path, source } => {
+                write!(f, "{}: {source}", path.display())
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Json { source, .. } => Some(source),
+            Self::Toml { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Deserialize the TOML `contents` of the file at `path`.
+pub fn parse_toml<T: DeserializeOwned>(
+    path: &Path,
+    contents: &str,
+) -> Result<T, Error> {
+    toml::from_str(contents).map_err(|source| Error::Toml {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Deserialize the JSON `contents` of the file at `path`.
+pub fn parse<T: DeserializeOwned>(
+    path: &Path,
+    contents: &str,
+) -> Result<T, Error> {
+    serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(
+        contents,
+    ))
+    .map_err(|source| Error::Json {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+pub fn read(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
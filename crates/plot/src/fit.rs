@@ -0,0 +1,264 @@
+//! Asymptotic complexity models fitted to the times of each dataset by least
+//! squares, for extrapolating to input sizes beyond the benchmarked ones.
+
+use std::fmt::{self, Display};
+
+use crate::{
+    benchmarks::{Input, Time, format_time},
+    cli::DataPoints,
+};
+
+/// Power-law exponent above which scaling counts as superlinear, tolerating
+/// measurement noise around linear scaling.
+const SUPERLINEAR_EXPONENT: f64 = 1.05;
+
+#[derive(Clone, Copy)]
+pub enum Model {
+    /// `a + b·n`, fitted by linear regression.
+    Linear { a: f64, b: f64 },
+
+    /// `a·n^k`, fitted by linear regression of `ln t = ln a + k·ln n`.
+    Power { a: f64, k: f64 },
+}
+
+impl Model {
+    pub fn evaluate(&self, n: f64) -> Time {
+        match self {
+            Self::Linear { a, b } => a + b * n,
+            Self::Power { a, k } => a * n.powf(*k),
+        }
+    }
+}
+
+impl Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Linear { a, b } => write!(f, "{a:.3e} + {b:.3e}·n"),
+            Self::Power { a, k } => write!(f, "{a:.3e}·n^{k:.3}"),
+        }
+    }
+}
+
+pub struct Fit {
+    pub model: Model,
+
+    /// Coefficient of determination of the model's times, computed in linear
+    /// space for both models so that they are comparable.
+    pub r_squared: f64,
+}
+
+pub struct Fits {
+    pub linear: Fit,
+    pub power: Fit,
+    pub source: String,
+}
+
+impl Fits {
+    /// Model with the higher coefficient of determination.
+    pub fn best(&self) -> &Fit {
+        if self.power.r_squared > self.linear.r_squared {
+            &self.power
+        } else {
+            &self.linear
+        }
+    }
+
+    pub fn superlinear(&self) -> bool {
+        matches!(self.power.model, Model::Power { k, .. } if k > SUPERLINEAR_EXPONENT)
+    }
+
+    /// Describe both models with their time extrapolated to `input`.
+    pub fn summary(&self, input: Input) -> String {
+        let describe = |fit: &Fit| {
+            format!(
+                "{} (R² = {:.4}): {}",
+                fit.model,
+                fit.r_squared,
+                format_time(fit.model.evaluate(input as f64))
+            )
+        };
+
+        format!(
+            "{} (best fit: {}):\n  linear: {}\n  power:  {}{}",
+            self.source,
+            match self.best().model {
+                Model::Linear { .. } => "linear",
+                Model::Power { .. } => "power",
+            },
+            describe(&self.linear),
+            describe(&self.power),
+            if self.superlinear() {
+                "\n  warning: superlinear scaling"
+            } else {
+                ""
+            }
+        )
+    }
+}
+
+/// Fit both models to each dataset's times at inputs of at least `min_input`,
+/// skipping datasets with fewer than two such inputs.
+pub fn fits(data: &[DataPoints], min_input: Input) -> Vec<Fits> {
+    data.iter()
+        .filter_map(|series| {
+            let points = series
+                .data
+                .iter()
+                .filter(|(x, y)| *x >= min_input && *x > 0 && *y > 0.0)
+                .map(|(x, y)| (*x as f64, *y))
+                .collect::<Vec<_>>();
+
+            let (a, b) = regression(&points)?;
+            let linear = Model::Linear { a, b };
+
+            let (ln_a, k) = regression(
+                &points
+                    .iter()
+                    .map(|(x, y)| (x.ln(), y.ln()))
+                    .collect::<Vec<_>>(),
+            )?;
+
+            let power = Model::Power { a: ln_a.exp(), k };
+
+            Some(Fits {
+                linear: Fit {
+                    model: linear,
+                    r_squared: r_squared(&points, linear),
+                },
+                power: Fit {
+                    model: power,
+                    r_squared: r_squared(&points, power),
+                },
+                source: series.label(),
+            })
+        })
+        .collect()
+}
+
+/// Attach the best fitting model of each dataset as its fitted curve, sampled
+/// at its inputs.
+pub fn fitted(data: &[DataPoints], fits: &[Fits]) -> Vec<DataPoints> {
+    data.iter()
+        .map(|series| DataPoints {
+            fitted: fits
+                .iter()
+                .find(|fits| fits.source == series.label())
+                .map(|fits| {
+                    series
+                        .data
+                        .iter()
+                        .map(|(x, _)| {
+                            (*x, fits.best().model.evaluate(*x as f64))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+
+            ..series.with_data(series.bounds.clone(), series.data.clone())
+        })
+        .collect()
+}
+
+/// Intercept and slope of the least squares line through `points`, unless
+/// fewer than two distinct x-values make it undetermined.
+fn regression(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let count = points.len() as f64;
+    let x_mean = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let y_mean = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+
+    let covariance = points
+        .iter()
+        .map(|(x, y)| (x - x_mean) * (y - y_mean))
+        .sum::<f64>();
+
+    let variance = points
+        .iter()
+        .map(|(x, _)| (x - x_mean).powi(2))
+        .sum::<f64>();
+
+    (variance > 0.0).then(|| {
+        let slope = covariance / variance;
+        (y_mean - slope * x_mean, slope)
+    })
+}
+
+fn r_squared(points: &[(f64, f64)], model: Model) -> f64 {
+    let mean = points.iter().map(|(_, y)| y).sum::<f64>() / points.len() as f64;
+
+    let residual = points
+        .iter()
+        .map(|(x, y)| (y - model.evaluate(*x)).powi(2))
+        .sum::<f64>();
+
+    let total = points.iter().map(|(_, y)| (y - mean).powi(2)).sum::<f64>();
+
+    1.0 - residual / total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() <= 1e-9 * expected.abs().max(1.0),
+            "{actual} should be close to {expected}"
+        );
+    }
+
+    #[test]
+    fn regression_recovers_line() {
+        let (a, b) =
+            regression(&[(1.0, 5.0), (2.0, 7.0), (4.0, 11.0)]).unwrap();
+
+        assert_close(a, 3.0);
+        assert_close(b, 2.0);
+    }
+
+    #[test]
+    fn regression_needs_distinct_inputs() {
+        assert!(regression(&[(2.0, 1.0), (2.0, 3.0)]).is_none());
+    }
+
+    #[test]
+    fn fits_linear_times() {
+        let series =
+            DataPoints::new("v01_base", vec![(10, 1.0), (20, 1.5), (40, 2.5)]);
+        let fits = fits(&[series], 0);
+
+        let Model::Linear { a, b } = fits[0].linear.model else {
+            panic!("linear fit should be linear");
+        };
+
+        assert_close(a, 0.5);
+        assert_close(b, 0.05);
+        assert_close(fits[0].linear.r_squared, 1.0);
+        assert!(matches!(fits[0].best().model, Model::Linear { .. }));
+    }
+
+    #[test]
+    fn fits_power_law_times() {
+        let series = DataPoints::new(
+            "v01_base",
+            vec![(10, 3e-4), (100, 3e-2), (1000, 3.0)],
+        );
+        let fits = fits(&[series], 0);
+
+        let Model::Power { a, k } = fits[0].power.model else {
+            panic!("power fit should be a power law");
+        };
+
+        assert_close(a, 3e-6);
+        assert_close(k, 2.0);
+        assert_close(fits[0].power.r_squared, 1.0);
+        assert!(matches!(fits[0].best().model, Model::Power { .. }));
+        assert!(fits[0].superlinear());
+    }
+
+    #[test]
+    fn fits_skip_small_inputs() {
+        let series = DataPoints::new("v01_base", vec![(10, 1.0), (20, 2.0)]);
+
+        assert!(fits(&[series], 20).is_empty());
+    }
+}
@@ -1,23 +1,224 @@
 mod benchmarks;
 mod cli;
+mod counters;
+mod criterion;
+mod error;
+mod fit;
+mod output;
 mod plot;
+mod regression;
+mod report;
+mod speedup;
+mod style;
+mod table;
+mod terminal;
+mod throughput;
 
-use cli::Cli;
-use plot::plot;
-use plotters::prelude::IntoLogRange;
+use std::process::ExitCode;
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let (data, x_range, y_range, log_base, linear_path, log_path) =
-        Cli::init()?;
+use cli::{Cli, DataPoints, Mode, Options, Plot, Scale, YRange};
+use plot::{bars, plot};
 
-    plot(&data, x_range.clone(), y_range.clone(), &linear_path)?;
+const FIT_NAME: &str = "fit";
+const INSTRUCTIONS_PER_ROW_DESC: &str = "Instructions per row";
+const INSTRUCTIONS_PER_ROW_NAME: &str = "instructions-per-row";
+const REPORT_NAME: &str = "report";
+const SPEEDUP_DESC: &str = "Speedup";
+const SPEEDUP_NAME: &str = "speedup";
+const SPEEDUP_SUMMARY_NAME: &str = "speedup-summary";
+const THROUGHPUT_BYTES_DESC: &str = "Throughput (MB/s)";
+const THROUGHPUT_BYTES_NAME: &str = "throughput-bytes";
+const THROUGHPUT_ROWS_DESC: &str = "Throughput (rows/s)";
+const THROUGHPUT_ROWS_NAME: &str = "throughput-rows";
+const TIME_DESC: &str = "Time (s)";
+const TIME_NAME: &str = "time";
 
-    plot(
-        &data,
-        x_range.log_scale().base(log_base),
+type Error = Box<dyn std::error::Error>;
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let options = match Cli::init()? {
+        Mode::Plot(options) => *options,
+
+        Mode::Regressions {
+            new,
+            old,
+            threshold,
+        } => {
+            for source in regression::incompatible(&old, &new) {
+                eprintln!(
+                    "warning: {source} benchmarked on different hardware or \
+                    software"
+                );
+            }
+
+            for source in regression::unmatched(&old, &new) {
+                eprintln!("warning: unmatched dataset: {source}");
+            }
+
+            let comparisons = regression::compare(&old, &new);
+
+            for comparison in &comparisons {
+                if !comparison.unbounded.is_empty() {
+                    eprintln!(
+                        "warning: {} inputs of {} skipped without a \
+                        confidence interval",
+                        comparison.unbounded.len(),
+                        comparison.source
+                    );
+                }
+            }
+
+            let (report, regressions) =
+                regression::report(&comparisons, threshold);
+
+            print!("{report}");
+
+            if regressions > 0 {
+                return Err(format!(
+                    "{regressions} regressions beyond {threshold}%"
+                )
+                .into());
+            }
+
+            return Ok(());
+        }
+
+        Mode::Table(data, markup) => {
+            print!("{}", table::table(&data, markup));
+            return Ok(());
+        }
+    };
+
+    let Options {
+        baseline,
+        data,
+        extrapolate,
+        fit_min_input,
+        output,
+        plots,
         y_range,
-        &log_path,
-    )?;
+        ..
+    } = &options;
+
+    if plots.contains(&Plot::Time) {
+        plot_scales(&options, data, Some(y_range), TIME_DESC, TIME_NAME)?;
+    }
+
+    // The report is a file of its own, with nothing to print in its place.
+    if plots.contains(&Plot::Report) && !output.terminal {
+        report::report(data, output, REPORT_NAME)?;
+    }
+
+    if plots.contains(&Plot::ThroughputRows) {
+        plot_scales(
+            &options,
+            &throughput::rows_per_second(data),
+            None,
+            THROUGHPUT_ROWS_DESC,
+            THROUGHPUT_ROWS_NAME,
+        )?;
+    }
+
+    if plots.contains(&Plot::ThroughputBytes)
+        && data.iter().any(|series| !series.bytes.is_empty())
+    {
+        plot_scales(
+            &options,
+            &throughput::megabytes_per_second(data),
+            None,
+            THROUGHPUT_BYTES_DESC,
+            THROUGHPUT_BYTES_NAME,
+        )?;
+    }
+
+    if plots.contains(&Plot::InstructionsPerRow)
+        && data.iter().any(|series| !series.instructions.is_empty())
+    {
+        plot_scales(
+            &options,
+            &counters::instructions_per_row(data),
+            None,
+            INSTRUCTIONS_PER_ROW_DESC,
+            INSTRUCTIONS_PER_ROW_NAME,
+        )?;
+    }
+
+    if let Some(baseline) = baseline {
+        let speedups = speedup::speedups(data, baseline)?;
+
+        if plots.contains(&Plot::Speedup) {
+            plot_scales(&options, &speedups, None, SPEEDUP_DESC, SPEEDUP_NAME)?;
+        }
+
+        if plots.contains(&Plot::SpeedupSummary)
+            && let Some(input) = speedup::largest_common_input(&speedups)
+        {
+            bars(&speedups, input, SPEEDUP_DESC, output, SPEEDUP_SUMMARY_NAME)?;
+        }
+    }
+
+    if plots.contains(&Plot::Fit) {
+        let fits = fit::fits(data, *fit_min_input);
+
+        for fits in &fits {
+            println!("{}", fits.summary(*extrapolate));
+        }
+
+        plot_scales(
+            &options,
+            &fit::fitted(data, &fits),
+            Some(y_range),
+            TIME_DESC,
+            FIT_NAME,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Plot `data` once per combination of scales, defaulting to a y-axis starting
+/// from zero on linear scales.
+fn plot_scales(
+    options: &Options,
+    data: &[DataPoints],
+    y_range: Option<&YRange>,
+    y_desc: &str,
+    name: &str,
+) -> Result<(), Error> {
+    for x_scale in &options.x_scales {
+        for y_scale in &options.y_scales {
+            let y_range = match y_range {
+                Some(y_range) => y_range.clone(),
+
+                None => Cli::y_range(
+                    data,
+                    matches!(y_scale, Scale::Linear).then_some(0.0),
+                    None,
+                )?,
+            };
+
+            plot(
+                data,
+                options.x_range.clone(),
+                y_range,
+                (*x_scale, *y_scale),
+                y_desc,
+                &options.output,
+                &format!("{name}-{x_scale}-{y_scale}"),
+            )?;
+        }
+    }
 
     Ok(())
 }
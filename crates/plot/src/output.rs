@@ -0,0 +1,126 @@
+//! Output format, size, and filenames of the plots.
+
+use clap::ValueEnum;
+use itertools::Itertools;
+
+use std::{
+    fmt::{self, Display},
+    path::PathBuf,
+};
+
+use crate::{cli::DataPoints, style::Style};
+
+/// Resolution the width and height are given in, at which no scaling occurs.
+const REFERENCE_DPI: f64 = 96.0;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// Portable Network Graphics, requiring the `bitmap` feature.
+    Png,
+
+    /// Scalable Vector Graphics.
+    Svg,
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+        }
+        .fmt(f)
+    }
+}
+
+/// Layout of the line plots.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum Grid {
+    /// A panel per dataset.
+    Iteration,
+
+    /// A single chart of all datasets.
+    #[default]
+    None,
+
+    /// A panel per pair of consecutive datasets.
+    Pair,
+}
+
+impl Grid {
+    /// Indices of the datasets drawn in each panel, keeping datasets of the
+    /// same name from several groups in the same panel.
+    pub fn panels(self, data: &[DataPoints]) -> Vec<Vec<usize>> {
+        let names = data
+            .iter()
+            .map(|series| series.source.as_str())
+            .unique()
+            .collect::<Vec<_>>();
+
+        let panel = |names: &[&str]| {
+            (0..data.len())
+                .filter(|index| names.contains(&data[*index].source.as_str()))
+                .collect()
+        };
+
+        match self {
+            Self::Iteration => {
+                names.iter().map(|name| panel(&[name])).collect()
+            }
+            Self::None => vec![(0..data.len()).collect()],
+            Self::Pair if names.len() > 1 => {
+                names.windows(2).map(panel).collect()
+            }
+            Self::Pair => vec![(0..data.len()).collect()],
+        }
+    }
+}
+
+pub struct Output {
+    /// Number of grid columns, defaulting to a roughly square grid.
+    pub columns: Option<usize>,
+
+    pub directory: PathBuf,
+    pub dpi: f64,
+    pub filename_template: String,
+    pub format: Format,
+    pub grid: Grid,
+    pub height: u32,
+    pub style: Style,
+
+    /// Whether to print the plots to the terminal instead of writing files.
+    pub terminal: bool,
+
+    pub width: u32,
+}
+
+impl Output {
+    /// Path of the plot `name`, substituting `{name}` and `{extension}` in the
+    /// filename template.
+    pub fn filename(&self, name: &str) -> PathBuf {
+        self.filename_with_extension(name, &self.format.to_string())
+    }
+
+    /// Path of the file `name`, with an extension independent of the format.
+    pub fn filename_with_extension(
+        &self,
+        name: &str,
+        extension: &str,
+    ) -> PathBuf {
+        self.directory.join(
+            self.filename_template
+                .replace("{name}", name)
+                .replace("{extension}", extension),
+        )
+    }
+
+    /// Scale a length in pixels at the reference resolution to the output
+    /// resolution.
+    pub fn scale(&self, pixels: u32) -> u32 {
+        (f64::from(pixels) * self.dpi / REFERENCE_DPI).round() as u32
+    }
+
+    /// Size of the output in pixels at the output resolution.
+    pub fn size(&self) -> (u32, u32) {
+        (self.scale(self.width), self.scale(self.height))
+    }
+}
@@ -1,88 +1,652 @@
 use plotters::{
     chart::ChartBuilder,
-    coord::ranged1d::{AsRangedCoord, ValueFormatter},
-    prelude::{IntoDrawingArea, PathElement, SVGBackend},
-    series::LineSeries,
-    style::{BLACK, Color, HSLColor, RGBColor, WHITE},
+    coord::{
+        Shift,
+        ranged1d::{
+            AsRangedCoord, IntoSegmentedCoord, SegmentValue, ValueFormatter,
+        },
+    },
+    drawing::DrawingArea,
+    element::{
+        Circle, Cross, EmptyElement, Polygon, Rectangle, Text, TriangleMarker,
+    },
+    prelude::{
+        DrawingBackend, IntoDrawingArea, IntoLogRange, PathElement, SVGBackend,
+    },
+    series::{DashedLineSeries, DottedLineSeries, LineSeries},
+    style::{Color, IntoFont, TextStyle},
 };
 
-use tap::{Pipe, Tap};
+#[cfg(feature = "bitmap")]
+use plotters::prelude::BitMapBackend;
+
+use itertools::Itertools;
+use tap::Pipe;
 
 use std::path::Path;
 
 use crate::{
-    benchmarks::{Input, Time},
-    cli::DataPoints,
+    benchmarks::{Input, Time, compatible},
+    cli::{DataPoints, Scale, XRange, YRange},
+    error,
+    output::{Format, Output},
+    style::{LineStyle, Marker},
+    terminal,
 };
 
-const BACKGROUND_COLOR: RGBColor = WHITE;
 const BACKGROUND_OPACITY: f64 = 0.8;
-const BORDER_COLOR: RGBColor = BLACK;
-const HSL_LIGHTNESS: f64 = 0.5;
-const HSL_SATURATION: f64 = 1.0;
-const LEGEND_LINE_LENGTH: i32 = 20;
-const OUTPUT_SIZE: (u32, u32) = (1000, 1000);
-const X_DESC: &str = "Rows";
+const BAND_OPACITY: f64 = 0.2;
+const BAR_HEADROOM: f64 = 1.1;
+const BAR_MARGIN: u32 = 20;
+const CAPTION_AREA_SIZE: u32 = 50;
+const CAPTION_LINE_HEIGHT: u32 = 20;
+const CAPTION_MARGIN: u32 = 10;
+const COMMIT_LENGTH: usize = 7;
+const DASH_LENGTH: u32 = 8;
+const DASH_SPACING: u32 = 4;
+const DOT_RADIUS: u32 = 1;
+const DOT_SPACING: u32 = 6;
+const FONT_FAMILY: &str = "sans-serif";
+const LEGEND_LINE_LENGTH: u32 = 20;
+const LINE_WIDTH: u32 = 1;
+const MARKER_SIZE: u32 = 4;
+const MESH_BOLD_OPACITY: f64 = 0.2;
+const MESH_LIGHT_OPACITY: f64 = 0.1;
+pub const X_DESC: &str = "Rows";
 const X_LABEL_AREA_SIZE: u32 = 30;
-const Y_DESC: &str = "Time (s)";
-const Y_LABEL_AREA_SIZE: u32 = 30;
+const Y_LABEL_AREA_SIZE: u32 = 60;
+
+type Error = Box<dyn std::error::Error>;
+
+/// Evaluate `$draw` with `$backend` bound to a backend of the output format
+/// writing to `$filename`.
+macro_rules! with_backend {
+    ($output:expr, $filename:expr, |$backend:ident| $draw:expr) => {
+        match $output.format {
+            #[cfg(feature = "bitmap")]
+            Format::Png => {
+                let $backend = BitMapBackend::new($filename, $output.size());
+                $draw
+            }
+
+            #[cfg(not(feature = "bitmap"))]
+            Format::Png => Err("PNG output requires the bitmap feature".into()),
+
+            Format::Svg => {
+                let $backend = SVGBackend::new($filename, $output.size());
+                $draw
+            }
+        }
+    };
+}
+
+/// Plot a line per dataset, surrounded by its error band, into the plot
+/// `name` with the x- and y-axis `scales`.
+pub fn plot(
+    data: &[DataPoints],
+    x_range: XRange,
+    y_range: YRange,
+    scales: (Scale, Scale),
+    y_desc: &str,
+    output: &Output,
+    name: &str,
+) -> Result<(), Error> {
+    let (data, x_range, y_range) = positive(data, x_range, y_range, scales)?;
+    let data = data.as_slice();
+    let y_desc = output.style.y_label.as_deref().unwrap_or(y_desc);
+
+    if output.terminal {
+        print!(
+            "{}\n{}\n",
+            output.style.title.as_deref().unwrap_or(name),
+            terminal::lines(
+                data,
+                &x_range,
+                &y_range,
+                scales,
+                y_desc,
+                &output.style
+            )
+        );
+
+        return Ok(());
+    }
+
+    let filename = output.filename(name);
+
+    match scales {
+        (Scale::Linear, Scale::Linear) => {
+            lines(data, x_range, y_range, y_desc, output, &filename)
+        }
+
+        (Scale::Linear, Scale::Log(y_base)) => lines(
+            data,
+            x_range,
+            y_range.log_scale().base(y_base),
+            y_desc,
+            output,
+            &filename,
+        ),
 
-pub fn plot<X, Y>(
+        (Scale::Log(x_base), Scale::Linear) => lines(
+            data,
+            x_range.log_scale().base(x_base),
+            y_range,
+            y_desc,
+            output,
+            &filename,
+        ),
+
+        (Scale::Log(x_base), Scale::Log(y_base)) => lines(
+            data,
+            x_range.log_scale().base(x_base),
+            y_range.log_scale().base(y_base),
+            y_desc,
+            output,
+            &filename,
+        ),
+    }?;
+
+    println!("Saved plot: {}", filename.display());
+
+    Ok(())
+}
+
+fn lines<X, Y>(
     data: &[DataPoints],
     x_range: X,
     y_range: Y,
+    y_desc: &str,
+    output: &Output,
     filename: &Path,
-) -> Result<(), Box<dyn std::error::Error>>
+) -> Result<(), Error>
 where
+    X: AsRangedCoord<Value = Input> + Clone,
+    Y: AsRangedCoord<Value = Time> + Clone,
+    X::CoordDescType: ValueFormatter<Input>,
+    Y::CoordDescType: ValueFormatter<Time>,
+{
+    let panels = output.grid.panels(data);
+
+    let columns = output
+        .columns
+        .unwrap_or_else(|| (panels.len() as f64).sqrt().ceil() as usize)
+        .clamp(1, panels.len().max(1));
+
+    with_backend!(output, filename, |backend| {
+        let area = chart_area(backend, data, output)?;
+
+        if let [panel] = &panels[..] {
+            return draw_lines(
+                area, data, panel, x_range, y_range, y_desc, output,
+            );
+        }
+
+        for (area, panel) in area
+            .split_evenly((panels.len().div_ceil(columns), columns))
+            .into_iter()
+            .zip(&panels)
+        {
+            let title = panel
+                .iter()
+                .map(|index| data[*index].source.as_str())
+                .unique()
+                .join(", ");
+
+            draw_lines(
+                area.titled(
+                    &title,
+                    font(output.style.font_sizes.label, output),
+                )?,
+                data,
+                panel,
+                x_range.clone(),
+                y_range.clone(),
+                y_desc,
+                output,
+            )?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Draw the datasets of `data` at the indices in `panel` on `area`.
+fn draw_lines<DB, X, Y>(
+    area: DrawingArea<DB, Shift>,
+    data: &[DataPoints],
+    panel: &[usize],
+    x_range: X,
+    y_range: Y,
+    y_desc: &str,
+    output: &Output,
+) -> Result<(), Error>
+where
+    DB: DrawingBackend,
+    DB::ErrorType: 'static,
     X: AsRangedCoord<Value = Input>,
     Y: AsRangedCoord<Value = Time>,
     X::CoordDescType: ValueFormatter<Input>,
     Y::CoordDescType: ValueFormatter<Time>,
 {
-    let mut chart = SVGBackend::new(&filename, OUTPUT_SIZE)
-        .into_drawing_area()
-        .tap(|area| {
-            area.fill(&BACKGROUND_COLOR)
-                .expect("drawing area should be fillable")
-        })
+    let mut chart = area
         .pipe_ref(ChartBuilder::on)
-        .x_label_area_size(X_LABEL_AREA_SIZE)
-        .y_label_area_size(Y_LABEL_AREA_SIZE)
+        .x_label_area_size(output.scale(X_LABEL_AREA_SIZE))
+        .y_label_area_size(output.scale(Y_LABEL_AREA_SIZE))
         .build_cartesian_2d(x_range, y_range)?;
 
+    let foreground = output.style.theme.foreground();
+
     chart
         .configure_mesh()
-        .x_desc(X_DESC)
-        .y_desc(Y_DESC)
+        .axis_desc_style(font(output.style.font_sizes.label, output))
+        .axis_style(foreground)
+        .bold_line_style(foreground.mix(MESH_BOLD_OPACITY))
+        .label_style(font(output.style.font_sizes.label, output))
+        .light_line_style(foreground.mix(MESH_LIGHT_OPACITY))
+        .x_desc(x_desc(output))
+        .y_desc(y_desc)
         .draw()?;
 
-    let series_count = data.len();
+    let legend_line_length = output.scale(LEGEND_LINE_LENGTH) as i32;
 
-    for (index, series) in data.iter().enumerate() {
-        let color = HSLColor(
-            index as f64 / series_count as f64,
-            HSL_SATURATION,
-            HSL_LIGHTNESS,
-        );
+    for (index, series) in panel.iter().map(|index| (*index, &data[*index])) {
+        let color = output.style.color(data, index);
+        let line_style = color.stroke_width(output.scale(LINE_WIDTH));
+        let style = output.style.series(series);
+
+        chart.draw_series(band(series).map(|band| {
+            Polygon::new(band, color.mix(BAND_OPACITY).filled())
+        }))?;
+
+        chart.draw_series(DashedLineSeries::new(
+            series.fitted.clone(),
+            output.scale(DASH_LENGTH),
+            output.scale(DASH_SPACING),
+            line_style,
+        ))?;
+
+        let marker_size = output.scale(MARKER_SIZE);
+        let points = series.data.iter().copied();
+
+        match style.marker {
+            Marker::Circle => {
+                chart.draw_series(
+                    points.map(|point| Circle::new(point, marker_size, color)),
+                )?;
+            }
+
+            Marker::Cross => {
+                chart.draw_series(
+                    points.map(|point| Cross::new(point, marker_size, color)),
+                )?;
+            }
 
+            Marker::None => {}
+
+            Marker::Square => {
+                let size = marker_size as i32;
+
+                chart.draw_series(points.map(|point| {
+                    EmptyElement::at(point)
+                        + Rectangle::new(
+                            [(-size, -size), (size, size)],
+                            color.filled(),
+                        )
+                }))?;
+            }
+
+            Marker::Triangle => {
+                chart.draw_series(points.map(|point| {
+                    TriangleMarker::new(point, marker_size, color.filled())
+                }))?;
+            }
+        }
+
+        let dot_radius = output.scale(DOT_RADIUS);
+
+        match style.line {
+            LineStyle::Dashed => chart.draw_series(DashedLineSeries::new(
+                series.data.clone(),
+                output.scale(DASH_LENGTH),
+                output.scale(DASH_SPACING),
+                line_style,
+            ))?,
+
+            LineStyle::Dotted => chart.draw_series(DottedLineSeries::new(
+                series.data.clone(),
+                0,
+                output.scale(DOT_SPACING),
+                move |point| Circle::new(point, dot_radius, color.filled()),
+            ))?,
+
+            LineStyle::Solid => chart.draw_series(LineSeries::new(
+                series.data.clone(),
+                line_style,
+            ))?,
+        }
+        .label(series.label())
+        .legend(move |(x, y)| {
+            PathElement::new(
+                vec![(x, y), (x + legend_line_length, y)],
+                line_style,
+            )
+        });
+    }
+
+    if let Some(position) = output.style.legend.position() {
         chart
-            .draw_series(LineSeries::new(series.data.clone(), color))?
-            .label(series.source.clone())
-            .legend(move |(x, y)| {
-                PathElement::new(
-                    vec![(x, y), (x + LEGEND_LINE_LENGTH, y)],
-                    color,
-                )
-            });
+            .configure_series_labels()
+            .background_style(
+                output.style.theme.background().mix(BACKGROUND_OPACITY),
+            )
+            .border_style(foreground)
+            .label_font(font(output.style.font_sizes.label, output))
+            .position(position)
+            .draw()?;
     }
 
+    Ok(())
+}
+
+/// Restrict `data` to positive values on logarithmic axes, which cannot
+/// represent others, starting such axes from their smallest positive value if
+/// their range does not.
+fn positive(
+    data: &[DataPoints],
+    x_range: XRange,
+    y_range: YRange,
+    (x_scale, y_scale): (Scale, Scale),
+) -> Result<(Vec<DataPoints>, XRange, YRange), Error> {
+    let x_log = matches!(x_scale, Scale::Log(_));
+    let y_log = matches!(y_scale, Scale::Log(_));
+
+    let keep = |x: Input, y: Time| (!x_log || x > 0) && (!y_log || y > 0.0);
+
+    let data = data
+        .iter()
+        .map(|series| DataPoints {
+            fitted: series
+                .fitted
+                .iter()
+                .copied()
+                .filter(|(x, y)| keep(*x, *y))
+                .collect(),
+
+            ..series.with_data(
+                series
+                    .bounds
+                    .iter()
+                    .filter(|(x, (lower, _))| keep(**x, *lower))
+                    .map(|(x, bounds)| (*x, *bounds))
+                    .collect(),
+                series
+                    .data
+                    .iter()
+                    .copied()
+                    .filter(|(x, y)| keep(*x, *y))
+                    .collect(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let x_range = if x_log && x_range.start <= 0 {
+        data.iter()
+            .flat_map(|series| series.data.iter().map(|(x, _)| *x))
+            .min()
+            .ok_or(error::Error::Scale {
+                axis: 'x',
+                min: x_range.start as f64,
+            })?..x_range.end
+    } else {
+        x_range
+    };
+
+    let y_range = if y_log && y_range.start <= 0.0 {
+        data.iter()
+            .flat_map(|series| series.data.iter().map(|(_, y)| *y))
+            .min_by(Time::total_cmp)
+            .ok_or(error::Error::Scale {
+                axis: 'y',
+                min: y_range.start,
+            })?..y_range.end
+    } else {
+        y_range
+    };
+
+    Ok((data, x_range, y_range))
+}
+
+/// Outline of the error band of `series`, tracing the upper bounds forwards
+/// and the lower bounds backwards, if it has bounds at all its inputs.
+fn band(series: &DataPoints) -> Option<Vec<(Input, Time)>> {
+    let bounds = series
+        .data
+        .iter()
+        .map(|(x, _)| series.bounds.get(x).map(|bounds| (*x, *bounds)))
+        .collect::<Option<Vec<_>>>()
+        .filter(|bounds| !bounds.is_empty())?;
+
+    bounds
+        .iter()
+        .map(|(x, (_, upper))| (*x, *upper))
+        .chain(bounds.iter().rev().map(|(x, (lower, _))| (*x, *lower)))
+        .collect::<Vec<_>>()
+        .pipe(Some)
+}
+
+/// Plot a bar per dataset with its value at `input`, skipping datasets without
+/// such a value.
+pub fn bars(
+    data: &[DataPoints],
+    input: Input,
+    y_desc: &str,
+    output: &Output,
+    name: &str,
+) -> Result<(), Error> {
+    let y_desc = output.style.y_label.as_deref().unwrap_or(y_desc);
+
+    let bars = data
+        .iter()
+        .enumerate()
+        .filter_map(|(index, series)| {
+            series
+                .data
+                .iter()
+                .find(|(x, _)| *x == input)
+                .map(|(_, y)| (index, series.label(), *y))
+        })
+        .collect::<Vec<_>>();
+
+    if output.terminal {
+        print!(
+            "{}\n{}\n",
+            output.style.title.as_deref().unwrap_or(name),
+            terminal::bars(
+                data,
+                &bars,
+                &format!("{y_desc} at {input} rows"),
+                &output.style
+            )
+        );
+
+        return Ok(());
+    }
+
+    let filename = output.filename(name);
+
+    with_backend!(output, &filename, |backend| draw_bars(
+        chart_area(backend, data, output)?,
+        data,
+        &bars,
+        input,
+        y_desc,
+        output,
+    ))?;
+
+    println!("Saved plot: {}", filename.display());
+
+    Ok(())
+}
+
+fn draw_bars<DB>(
+    area: DrawingArea<DB, Shift>,
+    data: &[DataPoints],
+    bars: &[(usize, String, Time)],
+    input: Input,
+    y_desc: &str,
+    output: &Output,
+) -> Result<(), Error>
+where
+    DB: DrawingBackend,
+    DB::ErrorType: 'static,
+{
+    let y_max = bars
+        .iter()
+        .map(|(_, _, y)| *y)
+        .fold(Time::NEG_INFINITY, Time::max);
+
+    let mut chart = area
+        .pipe_ref(ChartBuilder::on)
+        .margin(output.scale(BAR_MARGIN))
+        .x_label_area_size(output.scale(X_LABEL_AREA_SIZE))
+        .y_label_area_size(output.scale(Y_LABEL_AREA_SIZE))
+        .build_cartesian_2d(
+            (0..bars.len()).into_segmented(),
+            0.0..y_max * BAR_HEADROOM,
+        )?;
+
+    let foreground = output.style.theme.foreground();
+
     chart
-        .configure_series_labels()
-        .background_style(BACKGROUND_COLOR.mix(BACKGROUND_OPACITY))
-        .border_style(BORDER_COLOR)
+        .configure_mesh()
+        .axis_desc_style(font(output.style.font_sizes.label, output))
+        .axis_style(foreground)
+        .bold_line_style(foreground.mix(MESH_BOLD_OPACITY))
+        .disable_x_mesh()
+        .label_style(font(output.style.font_sizes.label, output))
+        .light_line_style(foreground.mix(MESH_LIGHT_OPACITY))
+        .x_desc(format!("{}: {input}", x_desc(output)))
+        .x_label_formatter(&|segment| match segment {
+            SegmentValue::CenterOf(bar) => bars
+                .get(*bar)
+                .map(|(_, source, _)| source.to_string())
+                .unwrap_or_default(),
+            _ => String::new(),
+        })
+        .y_desc(y_desc)
         .draw()?;
 
-    println!("Saved plot: {}", filename.display());
+    chart.draw_series(bars.iter().enumerate().map(
+        |(bar, (index, _, y))| {
+            Rectangle::new(
+                [
+                    (SegmentValue::Exact(bar), 0.0),
+                    (SegmentValue::Exact(bar + 1), *y),
+                ],
+                output.style.color(data, *index).filled(),
+            )
+        },
+    ))?;
+
+    chart.draw_series(bars.iter().enumerate().map(|(bar, (_, _, y))| {
+        Text::new(
+            format!("{y:.2}"),
+            (SegmentValue::CenterOf(bar), *y),
+            font(output.style.font_sizes.caption, output),
+        )
+    }))?;
 
     Ok(())
 }
+
+/// Create a drawing area on `backend` with the caption of `data` below it.
+fn chart_area<DB>(
+    backend: DB,
+    data: &[DataPoints],
+    output: &Output,
+) -> Result<DrawingArea<DB, Shift>, Error>
+where
+    DB: DrawingBackend,
+    DB::ErrorType: 'static,
+{
+    let area = backend.into_drawing_area();
+    area.fill(&output.style.theme.background())?;
+
+    let (chart_area, caption_area) = area
+        .split_vertically(output.size().1 - output.scale(CAPTION_AREA_SIZE));
+
+    let caption_margin = output.scale(CAPTION_MARGIN) as i32;
+    let caption_line_height = output.scale(CAPTION_LINE_HEIGHT) as i32;
+
+    for (index, line) in caption(data).iter().enumerate() {
+        caption_area.draw_text(
+            line,
+            &font(output.style.font_sizes.caption, output),
+            (
+                caption_margin,
+                caption_margin + index as i32 * caption_line_height,
+            ),
+        )?;
+    }
+
+    match &output.style.title {
+        Some(title) => chart_area
+            .titled(title, font(output.style.font_sizes.title, output))?,
+        None => chart_area,
+    }
+    .pipe(Ok)
+}
+
+/// Font of `size` scaled to the output resolution, in the theme's color.
+fn font(size: u32, output: &Output) -> TextStyle<'static> {
+    (FONT_FAMILY, output.scale(size))
+        .into_font()
+        .color(&output.style.theme.foreground())
+}
+
+/// Description of the x-axis, unless overridden.
+fn x_desc(output: &Output) -> &str {
+    output.style.x_label.as_deref().unwrap_or(X_DESC)
+}
+
+/// Describe the benchmarking system of the datasets, provided they share a
+/// known one.
+fn caption(data: &[DataPoints]) -> Vec<String> {
+    match data.first() {
+        Some(first)
+            if data.iter().all(|series| {
+                compatible(series.metadata.as_ref(), first.metadata.as_ref())
+            }) =>
+        {
+            let Some(metadata) = &first.metadata else {
+                return Vec::new();
+            };
+
+            vec![
+                metadata.hardware.to_string(),
+                format!(
+                    "{}, commit {}",
+                    metadata.software,
+                    data.iter()
+                        .filter_map(|series| series.metadata.as_ref())
+                        .map(|metadata| {
+                            metadata
+                                .commit
+                                .chars()
+                                .take(COMMIT_LENGTH)
+                                .collect::<String>()
+                        })
+                        .unique()
+                        .join(", ")
+                ),
+            ]
+        }
+
+        Some(_) => {
+            vec!["Benchmarked on different hardware or software".to_string()]
+        }
+
+        None => Vec::new(),
+    }
+}
@@ -0,0 +1,237 @@
+//! Regressions between old and new datasets, matched by name and input size,
+//! with changes judged significant when their confidence intervals are
+//! disjoint.
+//!
+//! Inputs without a confidence interval in either dataset, like in datasets
+//! predating their recording, are skipped rather than judged on their point
+//! estimates, which would report noise as significant.
+
+use itertools::Itertools;
+
+use std::fmt::Write;
+
+use crate::{
+    benchmarks::{Input, Time, compatible, format_time},
+    cli::DataPoints,
+};
+
+const PERCENT: f64 = 100.0;
+
+pub struct Change {
+    pub input: Input,
+    pub new: Time,
+    pub old: Time,
+
+    /// Whether the confidence intervals of the old and new times are disjoint.
+    pub significant: bool,
+}
+
+impl Change {
+    /// Relative change of the time in percent, positive for slowdowns.
+    pub fn percent(&self) -> f64 {
+        (self.new / self.old - 1.0) * PERCENT
+    }
+}
+
+pub struct Comparison {
+    pub changes: Vec<Change>,
+    pub source: String,
+
+    /// Common inputs skipped for lacking a confidence interval.
+    pub unbounded: Vec<Input>,
+}
+
+/// Compare the datasets present in both `old` and `new` at their common input
+/// sizes.
+pub fn compare(old: &[DataPoints], new: &[DataPoints]) -> Vec<Comparison> {
+    new.iter()
+        .filter_map(|new| {
+            let old = old.iter().find(|old| old.source == new.source)?;
+
+            let (changes, unbounded) = new
+                .data
+                .iter()
+                .filter_map(|(input, new_time)| {
+                    let (_, old_time) =
+                        old.data.iter().find(|(x, _)| x == input)?;
+
+                    let bounds =
+                        old.bounds.get(input).zip(new.bounds.get(input));
+
+                    Some(match bounds {
+                        Some((
+                            (old_lower, old_upper),
+                            (new_lower, new_upper),
+                        )) => Ok(Change {
+                            input: *input,
+                            new: *new_time,
+                            old: *old_time,
+                            significant: new_lower > old_upper
+                                || new_upper < old_lower,
+                        }),
+                        None => Err(*input),
+                    })
+                })
+                .partition_result();
+
+            Some(Comparison {
+                changes,
+                source: new.source.clone(),
+                unbounded,
+            })
+        })
+        .collect()
+}
+
+/// Render the comparisons, marking significant changes beyond `threshold`
+/// percent, and count the regressions among them.
+pub fn report(comparisons: &[Comparison], threshold: f64) -> (String, usize) {
+    let mut report = String::new();
+    let mut regressions = 0;
+
+    for comparison in comparisons {
+        writeln!(report, "{}", comparison.source)
+            .expect("writing to a string should succeed");
+
+        writeln!(
+            report,
+            "  {:>12}  {:>12}  {:>12}  {:>9}",
+            "Rows", "Old", "New", "Change"
+        )
+        .expect("writing to a string should succeed");
+
+        for change in &comparison.changes {
+            let percent = change.percent();
+
+            let verdict = if change.significant && percent > threshold {
+                regressions += 1;
+                "regression"
+            } else if change.significant && percent < -threshold {
+                "improvement"
+            } else {
+                ""
+            };
+
+            let line = format!(
+                "  {:>12}  {:>12}  {:>12}  {:>+8.2}%  {verdict}",
+                change.input,
+                format_time(change.old),
+                format_time(change.new),
+                percent,
+            );
+
+            writeln!(report, "{}", line.trim_end())
+                .expect("writing to a string should succeed");
+        }
+    }
+
+    (report, regressions)
+}
+
+/// Names of the datasets benchmarked on different hardware or software in
+/// `old` and `new`.
+pub fn incompatible(old: &[DataPoints], new: &[DataPoints]) -> Vec<String> {
+    new.iter()
+        .filter(|new| {
+            old.iter().any(|old| {
+                old.source == new.source
+                    && !compatible(old.metadata.as_ref(), new.metadata.as_ref())
+            })
+        })
+        .map(|series| series.source.clone())
+        .collect()
+}
+
+/// Names of the datasets present in only one of `old` and `new`.
+pub fn unmatched(old: &[DataPoints], new: &[DataPoints]) -> Vec<String> {
+    old.iter()
+        .chain(new)
+        .map(|series| series.source.as_str())
+        .filter(|source| {
+            !(old.iter().any(|series| series.source == *source)
+                && new.iter().any(|series| series.source == *source))
+        })
+        .unique()
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Time at an input, with its confidence interval if known.
+    type Point = (Input, Time, Option<(Time, Time)>);
+
+    fn series(data: &[Point]) -> DataPoints {
+        DataPoints::new("v01_base", Vec::new()).with_data(
+            data.iter()
+                .filter_map(|(x, _, bounds)| Some((*x, (*bounds)?)))
+                .collect(),
+            data.iter().map(|(x, y, _)| (*x, *y)).collect(),
+        )
+    }
+
+    #[test]
+    fn disjoint_intervals_are_significant() {
+        let comparisons = compare(
+            &[series(&[(10, 1.0, Some((0.9, 1.1)))])],
+            &[series(&[(10, 1.5, Some((1.2, 1.8)))])],
+        );
+
+        let change = &comparisons[0].changes[0];
+
+        assert!(change.significant);
+        assert_eq!(change.percent(), 50.0);
+        assert_eq!(report(&comparisons, 10.0).1, 1);
+        assert_eq!(report(&comparisons, 60.0).1, 0);
+    }
+
+    #[test]
+    fn overlapping_intervals_are_not_significant() {
+        let comparisons = compare(
+            &[series(&[(10, 1.0, Some((0.5, 1.5)))])],
+            &[series(&[(10, 1.5, Some((1.2, 1.8)))])],
+        );
+
+        assert!(!comparisons[0].changes[0].significant);
+        assert_eq!(report(&comparisons, 10.0).1, 0);
+    }
+
+    #[test]
+    fn improvements_are_not_regressions() {
+        let comparisons = compare(
+            &[series(&[(10, 2.0, Some((1.9, 2.1)))])],
+            &[series(&[(10, 1.0, Some((0.9, 1.1)))])],
+        );
+
+        let (report, regressions) = report(&comparisons, 10.0);
+
+        assert!(comparisons[0].changes[0].significant);
+        assert!(report.contains("improvement"));
+        assert_eq!(regressions, 0);
+    }
+
+    #[test]
+    fn inputs_without_intervals_are_skipped() {
+        let comparisons = compare(
+            &[series(&[(10, 1.0, None), (20, 2.0, Some((1.9, 2.1)))])],
+            &[series(&[
+                (10, 2.0, Some((1.9, 2.1))),
+                (20, 4.0, Some((3.9, 4.1))),
+                (30, 6.0, Some((5.9, 6.1))),
+            ])],
+        );
+
+        assert_eq!(comparisons[0].unbounded, [10]);
+
+        assert_eq!(
+            comparisons[0]
+                .changes
+                .iter()
+                .map(|change| change.input)
+                .collect::<Vec<_>>(),
+            [20]
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Self-contained interactive HTML report of the datasets, drawing its chart in
+//! the browser without fetching any external resources.
+
+use plotters::style::Color;
+use serde_json::json;
+
+use std::fs;
+
+use crate::{cli::DataPoints, output::Output, plot::X_DESC};
+
+const DATA_PLACEHOLDER: &str = "/* DATA */";
+const EXTENSION: &str = "html";
+const TEMPLATE: &str = include_str!("report.html");
+const Y_DESC: &str = "Time";
+
+type Error = Box<dyn std::error::Error>;
+
+/// Write the report `name`, embedding the times of `data` with their error
+/// bands and the metadata of each dataset.
+pub fn report(
+    data: &[DataPoints],
+    output: &Output,
+    name: &str,
+) -> Result<(), Error> {
+    let series = data
+        .iter()
+        .enumerate()
+        .map(|(index, series)| {
+            let (red, green, blue) = output.style.color(data, index).rgb();
+
+            json!({
+                "color": format!("rgb({red}, {green}, {blue})"),
+                "name": series.label(),
+                "points": series
+                    .data
+                    .iter()
+                    .map(|(x, y)| {
+                        let bounds = series.bounds.get(x);
+
+                        json!([
+                            x,
+                            y,
+                            bounds.map(|(lower, _)| lower),
+                            bounds.map(|(_, upper)| upper)
+                        ])
+                    })
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let metadata = data
+        .iter()
+        .map(|series| {
+            json!({
+                "commit": series
+                    .metadata
+                    .as_ref()
+                    .map(|metadata| &metadata.commit),
+                "hardware": series
+                    .metadata
+                    .as_ref()
+                    .map(|metadata| metadata.hardware.to_string()),
+                "name": series.label(),
+                "software": series
+                    .metadata
+                    .as_ref()
+                    .map(|metadata| metadata.software.to_string()),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // Escape closing tags, which would otherwise end the embedding script
+    // element early.
+    let data = json!({
+        "metadata": metadata,
+        "series": series,
+        "xDesc": X_DESC,
+        "yDesc": Y_DESC,
+    })
+    .to_string()
+    .replace("</", "<\\/");
+
+    let filename = output.filename_with_extension(name, EXTENSION);
+
+    fs::write(&filename, TEMPLATE.replace(DATA_PLACEHOLDER, &data))?;
+
+    println!("Saved report: {}", filename.display());
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! Speedups of datasets relative to a baseline dataset, computed at each input
+//! size both datasets were benchmarked with.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{benchmarks::Input, cli::DataPoints};
+
+type Error = Box<dyn std::error::Error>;
+
+/// Largest input size shared by all datasets.
+pub fn largest_common_input(data: &[DataPoints]) -> Option<Input> {
+    data.iter()
+        .map(|series| {
+            series.data.iter().map(|(x, _)| *x).collect::<BTreeSet<_>>()
+        })
+        .reduce(|common, inputs| &common & &inputs)?
+        .last()
+        .copied()
+}
+
+/// Divide the baseline time by each dataset's time, so that the baseline
+/// itself has a constant speedup of 1, comparing grouped datasets against the
+/// baseline of their group and skipping groups without one.
+///
+/// The bounds combine conservatively: the lower bound divides the fastest
+/// baseline time by the slowest time, and vice versa.
+pub fn speedups(
+    data: &[DataPoints],
+    baseline: &str,
+) -> Result<Vec<DataPoints>, Error> {
+    let baselines = data
+        .iter()
+        .filter(|series| series.source == baseline)
+        .map(|series| {
+            (
+                &series.group,
+                (
+                    &series.bounds,
+                    series.data.iter().copied().collect::<HashMap<_, _>>(),
+                ),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    if baselines.is_empty() {
+        return Err(format!("baseline dataset not found: {baseline}").into());
+    }
+
+    Ok(data
+        .iter()
+        .filter_map(|series| {
+            baselines
+                .get(&series.group)
+                .map(|baseline| (series, baseline))
+        })
+        .map(|(series, (baseline_bounds, baseline))| {
+            series.with_data(
+                series
+                    .bounds
+                    .iter()
+                    .filter_map(|(x, (lower, upper))| {
+                        baseline_bounds.get(x).map(
+                            |(baseline_lower, baseline_upper)| {
+                                (
+                                    *x,
+                                    (
+                                        baseline_lower / upper,
+                                        baseline_upper / lower,
+                                    ),
+                                )
+                            },
+                        )
+                    })
+                    .collect(),
+                series
+                    .data
+                    .iter()
+                    .filter_map(|(x, y)| {
+                        baseline.get(x).map(|time| (*x, time / y))
+                    })
+                    .collect(),
+            )
+        })
+        .collect())
+}
@@ -0,0 +1,287 @@
+//! Appearance of the plots, configured by a TOML file and overridden by command
+//! line flags, with series styled by dataset name to stay consistent across
+//! plots.
+
+use clap::ValueEnum;
+use itertools::Itertools;
+use plotters::{
+    chart::SeriesLabelPosition,
+    style::{BLACK, Color as _, HSLColor, RGBColor, WHITE},
+};
+use serde::Deserialize;
+
+use std::{collections::HashMap, str::FromStr};
+
+use crate::cli::DataPoints;
+
+const DARK_BACKGROUND: RGBColor = RGBColor(0x1e, 0x1e, 0x1e);
+const DARK_FOREGROUND: RGBColor = RGBColor(0xe0, 0xe0, 0xe0);
+const HSL_LIGHTNESS: f64 = 0.5;
+const HSL_SATURATION: f64 = 1.0;
+
+/// Color given as `#RRGGBB`.
+#[derive(Clone, Copy, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Color(pub RGBColor);
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid color {value:?}, expected #RRGGBB");
+
+        let hex = value
+            .strip_prefix('#')
+            .filter(|hex| hex.len() == 6)
+            .ok_or_else(invalid)?;
+
+        let channel = |index: usize| {
+            hex.get(index..index + 2)
+                .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+                .ok_or_else(invalid)
+        };
+
+        Ok(Self(RGBColor(channel(0)?, channel(2)?, channel(4)?)))
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Font sizes in points at 96 DPI.
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FontSizes {
+    /// Caption below the chart and bar labels.
+    pub caption: u32,
+
+    /// Axis descriptions, tick labels, and legend.
+    pub label: u32,
+
+    pub title: u32,
+}
+
+impl Default for FontSizes {
+    fn default() -> Self {
+        Self {
+            caption: 14,
+            label: 12,
+            title: 20,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Legend {
+    LowerLeft,
+    LowerMiddle,
+    LowerRight,
+    MiddleLeft,
+    MiddleMiddle,
+
+    #[default]
+    MiddleRight,
+
+    /// No legend.
+    None,
+
+    UpperLeft,
+    UpperMiddle,
+    UpperRight,
+}
+
+impl Legend {
+    pub fn position(self) -> Option<SeriesLabelPosition> {
+        match self {
+            Self::LowerLeft => Some(SeriesLabelPosition::LowerLeft),
+            Self::LowerMiddle => Some(SeriesLabelPosition::LowerMiddle),
+            Self::LowerRight => Some(SeriesLabelPosition::LowerRight),
+            Self::MiddleLeft => Some(SeriesLabelPosition::MiddleLeft),
+            Self::MiddleMiddle => Some(SeriesLabelPosition::MiddleMiddle),
+            Self::MiddleRight => Some(SeriesLabelPosition::MiddleRight),
+            Self::None => None,
+            Self::UpperLeft => Some(SeriesLabelPosition::UpperLeft),
+            Self::UpperMiddle => Some(SeriesLabelPosition::UpperMiddle),
+            Self::UpperRight => Some(SeriesLabelPosition::UpperRight),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum LineStyle {
+    Dashed,
+    Dotted,
+
+    #[default]
+    Solid,
+}
+
+impl FromStr for LineStyle {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        <Self as ValueEnum>::from_str(value, false)
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Marker {
+    Circle,
+    Cross,
+
+    #[default]
+    None,
+
+    Square,
+    Triangle,
+}
+
+impl FromStr for Marker {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        <Self as ValueEnum>::from_str(value, false)
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SeriesStyle {
+    /// Color overriding the evenly spaced default hue.
+    pub color: Option<Color>,
+
+    pub line: LineStyle,
+    pub marker: Marker,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Style {
+    pub font_sizes: FontSizes,
+    pub legend: Legend,
+
+    /// Series styles by dataset name, or by group if grouping.
+    pub series: HashMap<String, SeriesStyle>,
+
+    pub theme: Theme,
+    pub title: Option<String>,
+
+    /// Axis descriptions overriding those of each plot.
+    pub x_label: Option<String>,
+    pub y_label: Option<String>,
+}
+
+impl Style {
+    /// Color of the dataset at `index`, defaulting to evenly spaced hues per
+    /// dataset, or per group if grouping.
+    pub fn color(&self, data: &[DataPoints], index: usize) -> RGBColor {
+        let series = &data[index];
+
+        self.series(series).color.map_or_else(
+            || {
+                let (position, count) = match &series.group {
+                    Some(group) => {
+                        let groups = data
+                            .iter()
+                            .filter_map(|series| series.group.as_ref())
+                            .unique()
+                            .collect::<Vec<_>>();
+
+                        (
+                            groups
+                                .iter()
+                                .position(|other| *other == group)
+                                .unwrap_or_default(),
+                            groups.len(),
+                        )
+                    }
+
+                    None => (index, data.len()),
+                };
+
+                let (red, green, blue) = HSLColor(
+                    position as f64 / count as f64,
+                    HSL_SATURATION,
+                    HSL_LIGHTNESS,
+                )
+                .to_rgba()
+                .rgb();
+
+                RGBColor(red, green, blue)
+            },
+            |color| color.0,
+        )
+    }
+
+    /// Style of `series`, configured by its group if grouping, or else by its
+    /// name.
+    pub fn series(&self, series: &DataPoints) -> SeriesStyle {
+        series
+            .group
+            .as_ref()
+            .and_then(|group| self.series.get(group))
+            .or_else(|| self.series.get(&series.source))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    /// Light text and lines on a dark background.
+    Dark,
+
+    #[default]
+    Light,
+}
+
+impl Theme {
+    pub fn background(self) -> RGBColor {
+        match self {
+            Self::Dark => DARK_BACKGROUND,
+            Self::Light => WHITE,
+        }
+    }
+
+    /// Color of text, axes, and borders.
+    pub fn foreground(self) -> RGBColor {
+        match self {
+            Self::Dark => DARK_FOREGROUND,
+            Self::Light => BLACK,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_are_parsed() {
+        assert_eq!(
+            "#1e90ff".parse::<Color>().map(|color| color.0),
+            Ok(RGBColor(0x1e, 0x90, 0xff))
+        );
+        assert_eq!(
+            "#FF8000".parse::<Color>().map(|color| color.0),
+            Ok(RGBColor(0xff, 0x80, 0x00))
+        );
+
+        for color in ["", "#", "1e90ff", "#1e90f", "#1e90ff0", "#1g90ff", "red"]
+        {
+            assert!(
+                color.parse::<Color>().is_err(),
+                "{color:?} should be rejected"
+            );
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Tables of the dataset times by input size, with the speedup of each dataset
+//! over the previous one, for regenerating documentation.
+
+use clap::ValueEnum;
+use itertools::Itertools;
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    benchmarks::{self, Input, Time},
+    cli::DataPoints,
+    plot::X_DESC,
+};
+
+const SPEEDUP_PRECISION: usize = 2;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Markup {
+    Asciidoc,
+    Csv,
+    Markdown,
+}
+
+/// Render a row per input size and a time column per dataset, each followed by
+/// its speedup over the previous dataset.
+pub fn table(data: &[DataPoints], markup: Markup) -> String {
+    let times = data
+        .iter()
+        .map(|series| series.data.iter().copied().collect::<HashMap<_, _>>())
+        .collect::<Vec<_>>();
+
+    let header = [X_DESC.to_string()]
+        .into_iter()
+        .chain(data.iter().enumerate().flat_map(|(index, series)| {
+            [format!("{} time", series.label())].into_iter().chain(
+                (index > 0).then(|| format!("{} speedup", series.label())),
+            )
+        }))
+        .collect::<Vec<_>>();
+
+    let rows = data
+        .iter()
+        .flat_map(|series| series.data.iter().map(|(x, _)| *x))
+        .collect::<BTreeSet<Input>>()
+        .into_iter()
+        .map(|input| {
+            [input.to_string()]
+                .into_iter()
+                .chain(times.iter().enumerate().flat_map(|(index, series)| {
+                    let time = series.get(&input).copied();
+
+                    [time.map(|time| format_time(time, markup))]
+                        .into_iter()
+                        .chain((index > 0).then(|| {
+                            times[index - 1].get(&input).zip(time).map(
+                                |(previous, time)| {
+                                    format_speedup(previous / time, markup)
+                                },
+                            )
+                        }))
+                        .map(Option::unwrap_or_default)
+                }))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    match markup {
+        Markup::Asciidoc => asciidoc(&header, &rows),
+        Markup::Csv => csv(&header, &rows),
+        Markup::Markdown => markdown(&header, &rows),
+    }
+}
+
+fn asciidoc(header: &[String], rows: &[Vec<String>]) -> String {
+    let row = |cells: &[String]| {
+        cells
+            .iter()
+            .map(|cell| format!("|{}", cell.replace('|', "\\|")))
+            .join(" ")
+    };
+
+    [
+        format!("[cols=\"{}*>\",options=\"header\"]", header.len()),
+        "|===".to_string(),
+        row(header),
+    ]
+    .into_iter()
+    .chain(rows.iter().map(|cells| row(cells)))
+    .chain(["|===".to_string()])
+    .map(|line| line + "\n")
+    .collect()
+}
+
+fn csv(header: &[String], rows: &[Vec<String>]) -> String {
+    let row = |cells: &[String]| {
+        cells
+            .iter()
+            .map(|cell| {
+                if cell.contains([',', '"', '\n']) {
+                    format!("\"{}\"", cell.replace('"', "\"\""))
+                } else {
+                    cell.clone()
+                }
+            })
+            .join(",")
+    };
+
+    [row(header)]
+        .into_iter()
+        .chain(rows.iter().map(|cells| row(cells)))
+        .map(|line| line + "\n")
+        .collect()
+}
+
+fn markdown(header: &[String], rows: &[Vec<String>]) -> String {
+    let row = |cells: &[String]| {
+        format!(
+            "| {} |",
+            cells
+                .iter()
+                .map(|cell| cell.replace('|', "\\|"))
+                .join(" | ")
+        )
+    };
+
+    [row(header), format!("|{}", "---:|".repeat(header.len()))]
+        .into_iter()
+        .chain(rows.iter().map(|cells| row(cells)))
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// Format a speedup as a multiplier, keeping CSV numeric.
+fn format_speedup(speedup: f64, markup: Markup) -> String {
+    match markup {
+        Markup::Csv => format!("{speedup:.SPEEDUP_PRECISION$}"),
+        Markup::Asciidoc | Markup::Markdown => {
+            format!("{speedup:.SPEEDUP_PRECISION$}×")
+        }
+    }
+}
+
+/// Format a time in seconds with the largest unit it reaches, keeping CSV in
+/// seconds.
+fn format_time(seconds: Time, markup: Markup) -> String {
+    match markup {
+        Markup::Csv => seconds.to_string(),
+        Markup::Asciidoc | Markup::Markdown => benchmarks::format_time(seconds),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data() -> [DataPoints; 2] {
+        [
+            DataPoints::new("v01_base", vec![(10, 0.002), (20, 0.004_5)]),
+            DataPoints::new("v02_byte_file_parsing", vec![(10, 0.000_8)]),
+        ]
+    }
+
+    #[test]
+    fn asciidoc_table() {
+        assert_eq!(
+            table(&data(), Markup::Asciidoc),
+            concat!(
+                "[cols=\"4*>\",options=\"header\"]\n",
+                "|===\n",
+                "|Rows |v01_base time |v02_byte_file_parsing time ",
+                "|v02_byte_file_parsing speedup\n",
+                "|10 |2.000 ms |800.0 µs |2.50×\n",
+                "|20 |4.500 ms | |\n",
+                "|===\n",
+            )
+        );
+    }
+
+    #[test]
+    fn csv_table_keeps_seconds() {
+        assert_eq!(
+            table(&data(), Markup::Csv),
+            concat!(
+                "Rows,v01_base time,v02_byte_file_parsing time,",
+                "v02_byte_file_parsing speedup\n",
+                "10,0.002,0.0008,2.50\n",
+                "20,0.0045,,\n",
+            )
+        );
+    }
+
+    #[test]
+    fn markdown_table() {
+        assert_eq!(
+            table(&data(), Markup::Markdown),
+            concat!(
+                "| Rows | v01_base time | v02_byte_file_parsing time ",
+                "| v02_byte_file_parsing speedup |\n",
+                "|---:|---:|---:|---:|\n",
+                "| 10 | 2.000 ms | 800.0 µs | 2.50× |\n",
+                "| 20 | 4.500 ms |  |  |\n",
+            )
+        );
+    }
+
+    #[test]
+    fn cells_are_escaped() {
+        let header = ["a|b".to_string(), "c,\"d\"".to_string()];
+
+        assert_eq!(csv(&header, &[]), "a|b,\"c,\"\"d\"\"\"\n");
+        assert_eq!(
+            markdown(&header, &[]),
+            "| a\\|b | c,\"d\" |\n|---:|---:|\n"
+        );
+    }
+}
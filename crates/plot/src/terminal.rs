@@ -0,0 +1,345 @@
+//! Plots rendered as text for terminals without a graphical display, drawing
+//! lines with braille dots in each dataset's color, or with a distinct marker
+//! per dataset when color is unavailable.
+
+use plotters::style::{Color, RGBColor};
+use terminal_size::{Height, Width, terminal_size};
+
+use std::{
+    env,
+    io::{self, IsTerminal},
+};
+
+use crate::{
+    benchmarks::{Input, Time},
+    cli::{DataPoints, Scale, XRange, YRange},
+    plot::X_DESC,
+    style::Style,
+};
+
+/// Bits of the dots in a braille cell, indexed by column and row.
+const BRAILLE_DOTS: [[u8; 4]; 2] =
+    [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+const BRAILLE_BLANK: u32 = 0x2800;
+const BRAILLE_CELL: (usize, usize) = (2, 4);
+const DASH_LENGTH: usize = 3;
+const DEFAULT_SIZE: (usize, usize) = (80, 24);
+const MARKERS: [char; 6] = ['•', '+', 'x', 'o', '*', '#'];
+const MIN_HEIGHT: usize = 8;
+const RESET: &str = "\x1b[0m";
+const Y_LABEL_WIDTH: usize = 10;
+
+/// Whether the pixel at a step along a line is drawn.
+type Pattern = fn(usize) -> bool;
+
+/// Render a line per dataset with `scales`, along with its error band and
+/// fitted curve, sized to the terminal.
+pub fn lines(
+    data: &[DataPoints],
+    x_range: &XRange,
+    y_range: &YRange,
+    scales: (Scale, Scale),
+    y_desc: &str,
+    style: &Style,
+) -> String {
+    let colored = colored();
+    let (columns, rows) = size();
+
+    let width = columns.saturating_sub(Y_LABEL_WIDTH + 2).max(1);
+
+    // Leave room for the axes, labels, and legend below the chart.
+    let height = rows.saturating_sub(data.len() + 5).max(MIN_HEIGHT);
+
+    let cell_size = if colored { BRAILLE_CELL } else { (1, 1) };
+    let (pixel_width, pixel_height) =
+        (width * cell_size.0, height * cell_size.1);
+
+    let x = |value: Input| {
+        position(
+            value as f64,
+            x_range.start as f64,
+            x_range.end as f64,
+            scales.0,
+        )
+        .map(|x| x * (pixel_width - 1) as f64)
+    };
+
+    let y = |value: Time| {
+        position(value, y_range.start, y_range.end, scales.1)
+            .map(|y| (1.0 - y) * (pixel_height - 1) as f64)
+    };
+
+    // Each cell holds its braille dots or marker, and the dataset drawn last.
+    let mut cells = vec![vec![(0u8, None::<usize>); width]; height];
+
+    for (index, series) in data.iter().enumerate() {
+        let pixels = |points: &mut dyn Iterator<Item = (Input, Time)>| {
+            points
+                .filter_map(|(input, time)| Some((x(input)?, y(time)?)))
+                .collect::<Vec<_>>()
+        };
+
+        let bound = |bound: fn((Time, Time)) -> Time| {
+            pixels(&mut series.data.iter().filter_map(|(input, _)| {
+                Some((*input, bound(*series.bounds.get(input)?)))
+            }))
+        };
+
+        let mut plot = |(px, py): (f64, f64)| {
+            let (px, py) = (px.round() as usize, py.round() as usize);
+
+            if px < pixel_width && py < pixel_height {
+                let cell = &mut cells[py / cell_size.1][px / cell_size.0];
+
+                if colored {
+                    cell.0 |= BRAILLE_DOTS[px % cell_size.0][py % cell_size.1];
+                }
+
+                cell.1 = Some(index);
+            }
+        };
+
+        // The error band is outlined dotted and the fitted curve dashed, below
+        // the data drawn solid.
+        let lines: [(_, Pattern); 4] = [
+            (bound(|(lower, _)| lower), |step| step % 2 == 0),
+            (bound(|(_, upper)| upper), |step| step % 2 == 0),
+            (pixels(&mut series.fitted.iter().copied()), |step| {
+                step % (2 * DASH_LENGTH) < DASH_LENGTH
+            }),
+            (pixels(&mut series.data.iter().copied()), |_| true),
+        ];
+
+        for (pixels, drawn) in lines {
+            let mut step = 0;
+
+            for pair in pixels.windows(2) {
+                let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+                let steps =
+                    (x1 - x0).abs().max((y1 - y0).abs()).ceil().max(1.0);
+
+                for segment_step in 0..steps as usize {
+                    if drawn(step) {
+                        let t = segment_step as f64 / steps;
+                        plot((x0 + (x1 - x0) * t, y0 + (y1 - y0) * t));
+                    }
+
+                    step += 1;
+                }
+            }
+
+            if let Some(pixel) = pixels.last().copied()
+                && drawn(step)
+            {
+                plot(pixel);
+            }
+        }
+    }
+
+    let mut output = format!("{y_desc}\n");
+
+    for (row, cells) in cells.iter().enumerate() {
+        let (label, tick) = match row {
+            0 => (format_number(y_range.end), '┤'),
+            _ if row == height - 1 => (format_number(y_range.start), '┤'),
+            _ => (String::new(), '│'),
+        };
+
+        output += &format!("{label:>Y_LABEL_WIDTH$} {tick}");
+
+        for (dots, index) in cells {
+            output += &match index {
+                Some(index) if colored => format!(
+                    "{}{}{RESET}",
+                    escape(style.color(data, *index)),
+                    char::from_u32(BRAILLE_BLANK + u32::from(*dots))
+                        .expect("braille dots should be a valid character")
+                ),
+
+                Some(index) => MARKERS[index % MARKERS.len()].to_string(),
+                None => " ".to_string(),
+            };
+        }
+
+        output += "\n";
+    }
+
+    let start = x_range.start.to_string();
+    let end = x_range.end.to_string();
+
+    output += &format!(
+        "{:Y_LABEL_WIDTH$} └{}\n{:Y_LABEL_WIDTH$}  {start}{end:>padding$}\n",
+        "",
+        "─".repeat(width),
+        "",
+        padding = width.saturating_sub(start.len()),
+    );
+
+    output += &format!(
+        "{:Y_LABEL_WIDTH$}  {:^width$}\n",
+        "",
+        format!(
+            "{} ({})",
+            style.x_label.as_deref().unwrap_or(X_DESC),
+            scales.0
+        )
+    );
+
+    output + &legend(data, colored, style)
+}
+
+/// Render a horizontal bar per labelled value, sized to the terminal.
+pub fn bars(
+    data: &[DataPoints],
+    bars: &[(usize, String, Time)],
+    y_desc: &str,
+    style: &Style,
+) -> String {
+    let colored = colored();
+
+    let label_width = bars
+        .iter()
+        .map(|(_, label, _)| label.chars().count())
+        .max()
+        .unwrap_or_default();
+
+    let width = size()
+        .0
+        .saturating_sub(label_width + Y_LABEL_WIDTH + 3)
+        .max(1);
+    let max = bars.iter().map(|(_, _, y)| *y).fold(0.0, Time::max);
+
+    let mut output = format!("{y_desc}\n");
+
+    for (index, label, y) in bars {
+        let length = (y / max * width as f64).round() as usize;
+
+        let bar = if colored {
+            format!(
+                "{}{}{RESET}",
+                escape(style.color(data, *index)),
+                "█".repeat(length)
+            )
+        } else {
+            MARKERS[index % MARKERS.len()].to_string().repeat(length)
+        };
+
+        output +=
+            &format!("{label:>label_width$} │{bar} {}\n", format_number(*y));
+    }
+
+    output
+}
+
+/// Whether to color the output, following the `NO_COLOR` convention.
+fn colored() -> bool {
+    io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none()
+}
+
+/// ANSI escape sequence of a dataset's plot color.
+fn escape(color: RGBColor) -> String {
+    let (red, green, blue) = color.rgb();
+    format!("\x1b[38;2;{red};{green};{blue}m")
+}
+
+fn format_number(value: f64) -> String {
+    if value != 0.0 && !(1e-2..1e4).contains(&value.abs()) {
+        format!("{value:.2e}")
+    } else {
+        format!("{value:.3}")
+    }
+}
+
+fn legend(data: &[DataPoints], colored: bool, style: &Style) -> String {
+    data.iter()
+        .enumerate()
+        .map(|(index, series)| {
+            if colored {
+                format!(
+                    "{}■{RESET} {}\n",
+                    escape(style.color(data, index)),
+                    series.label()
+                )
+            } else {
+                format!(
+                    "{} {}\n",
+                    MARKERS[index % MARKERS.len()],
+                    series.label()
+                )
+            }
+        })
+        .collect()
+}
+
+/// Relative position of `value` within `start..end` on `scale`, unless it is
+/// outside the range or not positive on a logarithmic scale. A range of a
+/// single value, such as that of a single input, has it centered like plotters
+/// does.
+fn position(value: f64, start: f64, end: f64, scale: Scale) -> Option<f64> {
+    let transform = |value: f64| match scale {
+        Scale::Linear => value,
+        Scale::Log(_) => value.ln(),
+    };
+
+    if let Scale::Log(_) = scale
+        && (value <= 0.0 || start <= 0.0)
+    {
+        return None;
+    }
+
+    if start == end {
+        return (value == start).then_some(0.5);
+    }
+
+    let position = (transform(value) - transform(start))
+        / (transform(end) - transform(start));
+
+    (0.0..=1.0).contains(&position).then_some(position)
+}
+
+/// Terminal size in columns and rows, falling back to the `COLUMNS` and `LINES`
+/// environment variables and then to the default size.
+fn size() -> (usize, usize) {
+    terminal_size()
+        .map(|(Width(columns), Height(rows))| {
+            (usize::from(columns), usize::from(rows))
+        })
+        .unwrap_or_else(|| {
+            let variable = |name, default| {
+                env::var(name)
+                    .ok()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or(default)
+            };
+
+            (
+                variable("COLUMNS", DEFAULT_SIZE.0),
+                variable("LINES", DEFAULT_SIZE.1),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_are_relative() {
+        assert_eq!(position(15.0, 10.0, 20.0, Scale::Linear), Some(0.5));
+        assert!(
+            position(100.0, 10.0, 1000.0, Scale::Log(10.0))
+                .is_some_and(|position| (position - 0.5).abs() < 1e-12)
+        );
+        assert_eq!(position(30.0, 10.0, 20.0, Scale::Linear), None);
+        assert_eq!(position(0.0, 0.0, 20.0, Scale::Log(10.0)), None);
+    }
+
+    #[test]
+    fn single_value_ranges_are_centered() {
+        for scale in [Scale::Linear, Scale::Log(2.0)] {
+            assert_eq!(position(32.0, 32.0, 32.0, scale), Some(0.5));
+            assert_eq!(position(16.0, 32.0, 32.0, scale), None);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Throughputs of datasets, derived from the time taken for each input size.
+
+use crate::cli::DataPoints;
+
+const BYTES_PER_MEGABYTE: f64 = 1e6;
+
+/// Convert each time to megabytes per second, skipping inputs of unknown size.
+///
+/// The bounds swap, since the upper time bound is the lower throughput bound.
+pub fn megabytes_per_second(data: &[DataPoints]) -> Vec<DataPoints> {
+    data.iter()
+        .map(|series| {
+            series.with_data(
+                series
+                    .bounds
+                    .iter()
+                    .filter_map(|(x, (lower, upper))| {
+                        series.bytes.get(x).map(|bytes| {
+                            let megabytes = *bytes as f64 / BYTES_PER_MEGABYTE;
+                            (*x, (megabytes / upper, megabytes / lower))
+                        })
+                    })
+                    .collect(),
+                series
+                    .data
+                    .iter()
+                    .filter_map(|(x, y)| {
+                        series.bytes.get(x).map(|bytes| {
+                            (*x, *bytes as f64 / BYTES_PER_MEGABYTE / y)
+                        })
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Convert each time to rows per second.
+pub fn rows_per_second(data: &[DataPoints]) -> Vec<DataPoints> {
+    data.iter()
+        .map(|series| {
+            series.with_data(
+                series
+                    .bounds
+                    .iter()
+                    .map(|(x, (lower, upper))| {
+                        (*x, (*x as f64 / upper, *x as f64 / lower))
+                    })
+                    .collect(),
+                series
+                    .data
+                    .iter()
+                    .map(|(x, y)| (*x, *x as f64 / y))
+                    .collect(),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,61 @@
+use serde::Serialize;
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use criterion_messages::Estimate;
+
+use crate::{counters::Counters, metadata::Metadata};
+
+pub type Bytes = u64;
+pub type Input = u64;
+pub type Time = f64;
+
+#[derive(Serialize)]
+pub struct Benchmark {
+    /// Allocations of a single run, outside of the measurements.
+    #[cfg(feature = "allocations")]
+    #[serde(flatten)]
+    pub allocations: iterations::allocations::Allocations,
+
+    pub bytes: Bytes,
+
+    /// Hardware performance counters of a single run, outside of the
+    /// measurements, if requested and available.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counters: Option<Counters>,
+
+    pub input: Input,
+    pub mean: Estimate,
+    pub median: Estimate,
+    pub std_dev: Time,
+
+    /// Mean time, duplicating `mean.estimate` for compatibility with datasets
+    /// predating the confidence intervals.
+    pub time: Time,
+}
+
+#[derive(Serialize)]
+pub struct Benchmarks<'a> {
+    pub benchmarks: Vec<Benchmark>,
+    pub metadata: &'a Metadata,
+    pub name: String,
+}
+
+impl Benchmarks<'_> {
+    /// Write the dataset to `<directory>/<name>.json`.
+    pub fn write(&self, directory: &Path) -> io::Result<()> {
+        let path = directory.join(format!("{}.json", self.name));
+        let mut file = BufWriter::new(File::create(&path)?);
+
+        serde_json::to_writer_pretty(&mut file, self)?;
+        writeln!(file)?;
+
+        eprintln!("Saved benchmarks: {}", path.display());
+
+        Ok(())
+    }
+}
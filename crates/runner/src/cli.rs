@@ -0,0 +1,139 @@
+use clap::{
+    Parser, ValueEnum,
+    ValueHint::{AnyPath, DirPath, FilePath},
+};
+
+use itertools::Itertools;
+use regex::Regex;
+
+use std::path::PathBuf;
+
+use iterations::{Iteration, input::Input};
+
+type Error = Box<dyn std::error::Error>;
+type Inputs = Vec<Input>;
+type Iterations = Vec<Iteration>;
+
+/// Page cache state of the inputs before each measurement.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum Cache {
+    /// Drop the cached pages of the input with
+    /// `posix_fadvise(POSIX_FADV_DONTNEED)`, measuring reads from the storage
+    /// device.
+    Cold,
+
+    /// Keep the input cached after the warm-up.
+    #[default]
+    Warm,
+}
+
+impl Cache {
+    /// Value of the `CACHE` environment variable read by the benchmarks.
+    pub fn env(self) -> &'static str {
+        match self {
+            Self::Cold => "cold",
+            Self::Warm => "warm",
+        }
+    }
+}
+
+/// Benchmarking options selected on the command line.
+pub struct Options {
+    pub cache: Cache,
+    pub counters: bool,
+    pub inputs: Inputs,
+    pub iterations: Iterations,
+    pub output_directory: PathBuf,
+    pub pci_ids: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+#[command(version, about, long_about = None)]
+pub struct Cli {
+    /// Page cache state of the inputs before each measurement.
+    #[arg(long, value_enum, default_value_t)]
+    cache: Cache,
+
+    /// Count instructions, cycles, cache misses, and branch misses of a single
+    /// run per input with hardware performance counters, where available.
+    #[arg(long)]
+    counters: bool,
+
+    /// Paths to the input files, or directories of input files.
+    #[arg(
+        value_name = "INPUT",
+        value_hint = AnyPath,
+        required = true,
+    )]
+    input: Vec<PathBuf>,
+
+    /// Regex selecting the inputs to benchmark, matched against their entire
+    /// number of rows.
+    #[arg(long, value_name = "REGEX", default_value = ".*")]
+    input_filter: String,
+
+    /// Regex selecting the iterations to benchmark.
+    #[arg(long, value_name = "REGEX", default_value = ".*")]
+    iteration_filter: String,
+
+    /// Directory to write the JSON datasets to.
+    #[arg(
+        short,
+        long,
+        value_name = "DIR",
+        default_value = "benchmarks",
+        value_hint = DirPath
+    )]
+    output_directory: PathBuf,
+
+    /// Path to the PCI ID database used to name the GPU.
+    #[arg(long, value_name = "FILE", value_hint = FilePath)]
+    pci_ids: Option<PathBuf>,
+}
+
+impl Cli {
+    pub fn init() -> Result<Options, Error> {
+        let cli = Cli::parse();
+
+        eprintln!("iteration filter: {}", cli.iteration_filter);
+        eprintln!("input filter: {}", cli.input_filter);
+
+        let iterations = Self::iterations(&cli.iteration_filter)?;
+        let inputs = Self::inputs(&cli.input, &cli.input_filter)?;
+
+        eprintln!("selected iterations: {}", iterations.iter().join(", "));
+
+        eprintln!(
+            "selected inputs: {}",
+            inputs.iter().map(|input| input.rows).join(", ")
+        );
+
+        Ok(Options {
+            cache: cli.cache,
+            counters: cli.counters,
+            inputs,
+            iterations,
+            output_directory: cli.output_directory,
+            pci_ids: cli.pci_ids,
+        })
+    }
+
+    fn inputs(paths: &[PathBuf], filter: &str) -> Result<Inputs, Error> {
+        let filter = Regex::new(&format!("^(?:{filter})$"))?;
+
+        Ok(Input::from_paths(paths.iter().cloned())?
+            .into_iter()
+            .filter(|input| filter.is_match(&input.rows.to_string()))
+            .collect())
+    }
+
+    fn iterations(filter: &str) -> Result<Iterations, Error> {
+        let filter = Regex::new(filter)?;
+
+        Ok(Iteration::value_variants()
+            .iter()
+            .filter(|iteration| filter.is_match(&iteration.to_string()))
+            .cloned()
+            .collect())
+    }
+}
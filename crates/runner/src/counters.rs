@@ -0,0 +1,144 @@
+//! Hardware performance counters of a single run of an iteration, read with
+//! `perf_event_open`, which virtual machines and a restrictive
+//! `perf_event_paranoid` commonly leave unavailable.
+
+use serde::Serialize;
+
+/// Counts of user-space events, each unknown if its counter is unavailable.
+#[derive(Clone, Copy, Serialize)]
+pub struct Counters {
+    pub branch_misses: Option<u64>,
+    pub cache_misses: Option<u64>,
+    pub cycles: Option<u64>,
+    pub instructions: Option<u64>,
+}
+
+/// Open counters of the process, inherited by the threads and processes it
+/// creates afterwards.
+pub struct Events {
+    #[cfg(target_os = "linux")]
+    counters: [Option<std::fs::File>; 4],
+}
+
+#[cfg(target_os = "linux")]
+impl Events {
+    /// Open the counters, returning `None` if none is available.
+    ///
+    /// Counters are only inherited by threads created afterwards, so this
+    /// should be called before spawning any thread.
+    pub fn open() -> Option<Self> {
+        use perf_event_open_sys::bindings::{
+            self, perf_event_attr, perf_hw_id_PERF_COUNT_HW_BRANCH_MISSES,
+            perf_hw_id_PERF_COUNT_HW_CACHE_MISSES,
+            perf_hw_id_PERF_COUNT_HW_CPU_CYCLES,
+            perf_hw_id_PERF_COUNT_HW_INSTRUCTIONS,
+        };
+
+        use std::{fs::File, os::fd::FromRawFd};
+
+        let counters = [
+            perf_hw_id_PERF_COUNT_HW_BRANCH_MISSES,
+            perf_hw_id_PERF_COUNT_HW_CACHE_MISSES,
+            perf_hw_id_PERF_COUNT_HW_CPU_CYCLES,
+            perf_hw_id_PERF_COUNT_HW_INSTRUCTIONS,
+        ]
+        .map(|config| {
+            let mut attr = perf_event_attr {
+                config: config.into(),
+                size: size_of::<perf_event_attr>() as u32,
+                type_: bindings::perf_type_id_PERF_TYPE_HARDWARE,
+                ..Default::default()
+            };
+
+            attr.set_disabled(1);
+            attr.set_exclude_hv(1);
+            attr.set_exclude_kernel(1);
+            attr.set_inherit(1);
+
+            // SAFETY: `attr` is a valid attribute, counting the calling
+            // process on any CPU without a group.
+            let fd = unsafe {
+                perf_event_open_sys::perf_event_open(
+                    &mut attr,
+                    0,
+                    -1,
+                    -1,
+                    bindings::PERF_FLAG_FD_CLOEXEC.into(),
+                )
+            };
+
+            // SAFETY: A non-negative file descriptor is newly opened and owned
+            // by nothing else.
+            (fd >= 0).then(|| unsafe { File::from_raw_fd(fd) })
+        });
+
+        counters
+            .iter()
+            .any(Option::is_some)
+            .then_some(Self { counters })
+    }
+
+    /// Run `f`, counting its events.
+    ///
+    /// Counts are read before and after running, since resetting a counter
+    /// keeps the counts of its exited inherited threads.
+    pub fn count(&self, f: impl FnOnce()) -> Counters {
+        use perf_event_open_sys::ioctls;
+
+        use std::{array, io::Read, os::fd::AsRawFd};
+
+        let read = || {
+            self.counters.each_ref().map(|counter| {
+                let mut file = counter.as_ref()?;
+                let mut value = [0; size_of::<u64>()];
+
+                file.read_exact(&mut value).ok()?;
+                Some(u64::from_ne_bytes(value))
+            })
+        };
+
+        let ioctl = |ioctl: unsafe fn(i32, u32) -> i32| {
+            for counter in self.counters.iter().flatten() {
+                // SAFETY: The file descriptor is an open counter, and `ioctl`
+                // applies to it and its inherited counters.
+                unsafe { ioctl(counter.as_raw_fd(), 0) };
+            }
+        };
+
+        let before = read();
+
+        ioctl(ioctls::ENABLE);
+        f();
+        ioctl(ioctls::DISABLE);
+
+        let after = read();
+
+        let [branch_misses, cache_misses, cycles, instructions] =
+            array::from_fn(|index| Some(after[index]? - before[index]?));
+
+        Counters {
+            branch_misses,
+            cache_misses,
+            cycles,
+            instructions,
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+impl Events {
+    pub fn open() -> Option<Self> {
+        None
+    }
+
+    pub fn count(&self, f: impl FnOnce()) -> Counters {
+        f();
+
+        Counters {
+            branch_misses: None,
+            cache_misses: None,
+            cycles: None,
+            instructions: None,
+        }
+    }
+}
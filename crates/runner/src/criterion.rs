@@ -0,0 +1,113 @@
+//! Run the generated criterion benchmarks through `cargo criterion`, collecting
+//! the time estimates of each input from its JSON messages.
+
+use tap::Pipe;
+
+use std::{
+    env,
+    io::BufReader,
+    process::{Command, Stdio},
+};
+
+use criterion_messages::{Message, std_dev};
+use iterations::{Iteration, input::Input};
+
+use crate::{benchmarks::Benchmark, cli::Cache, counters::Events};
+
+type Error = Box<dyn std::error::Error>;
+
+/// Find the input of a benchmark ID, formatted as `<iteration>/<input
+/// name>/<rows>`.
+fn benchmark_input<'a>(id: &str, inputs: &'a [Input]) -> Option<&'a Input> {
+    let mut parts = id.splitn(3, '/').skip(1);
+
+    let name = parts.next()?;
+    let rows = parts.next()?.parse::<u64>().ok()?;
+
+    inputs
+        .iter()
+        .find(|input| input.name() == name && input.rows == rows)
+}
+
+pub fn run(
+    iteration: &Iteration,
+    inputs: &[Input],
+    cache: Cache,
+    events: Option<&Events>,
+) -> Result<Vec<Benchmark>, Error> {
+    let mut child = Command::new("cargo")
+        .args(["criterion", "--bench", "bench", "--message-format", "json"])
+        .arg("--")
+        .arg(format!("^{iteration}/"))
+        .env("CACHE", cache.env())
+        .env(
+            "INPUT",
+            env::join_paths(inputs.iter().map(|input| &input.path))?,
+        )
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    let benchmarks = child
+        .stdout
+        .take()
+        .expect("stdout should be piped")
+        .pipe(BufReader::new)
+        .pipe(serde_json::Deserializer::from_reader)
+        .into_iter::<Message>()
+        .filter_map(|message| match message {
+            Ok(Message::BenchmarkComplete {
+                id,
+                iteration_count,
+                mean,
+                measured_values,
+                median,
+                ..
+            }) => Some(
+                benchmark_input(&id, inputs)
+                    .ok_or_else(|| format!("invalid benchmark ID: {id}").into())
+                    .map(|input| {
+                        (
+                            input,
+                            mean,
+                            median,
+                            std_dev(&measured_values, &iteration_count),
+                        )
+                    }),
+            ),
+
+            Ok(Message::Other) => None,
+            Err(error) => Some(Err(error.into())),
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let status = child.wait()?;
+
+    if !status.success() {
+        return Err(format!("cargo criterion failed: {status}").into());
+    }
+
+    // Single runs are counted once `cargo criterion` exited, since the
+    // hardware performance counters are inherited by and enabled for every
+    // child process.
+    Ok(benchmarks
+        .into_iter()
+        .map(|(input, mean, median, std_dev)| {
+            let run = || {
+                iterations::run(iteration.clone(), &input.path);
+            };
+
+            Benchmark {
+                #[cfg(feature = "allocations")]
+                allocations: iterations::allocations::count(run).1,
+
+                bytes: input.bytes,
+                counters: events.map(|events| events.count(run)),
+                input: input.rows,
+                std_dev,
+                time: mean.estimate,
+                mean,
+                median,
+            }
+        })
+        .collect())
+}
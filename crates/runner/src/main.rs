@@ -0,0 +1,58 @@
+mod benchmarks;
+mod cli;
+mod counters;
+mod criterion;
+mod metadata;
+
+use std::fs;
+
+use benchmarks::Benchmarks;
+use cli::{Cli, Options};
+use counters::Events;
+use metadata::Metadata;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let Options {
+        cache,
+        counters,
+        inputs,
+        iterations,
+        output_directory,
+        pci_ids,
+    } = Cli::init()?;
+
+    // Opened before any thread is spawned, to count the threads of every
+    // iteration.
+    let events = counters.then(Events::open).and_then(|events| {
+        if events.is_none() {
+            eprintln!(
+                "warning: hardware performance counters unavailable, skipping \
+                them"
+            );
+        }
+
+        events
+    });
+
+    let metadata = Metadata::collect(pci_ids.as_deref())?;
+
+    fs::create_dir_all(&output_directory)?;
+
+    for (index, iteration) in iterations.iter().enumerate() {
+        eprintln!("[{}/{}] {iteration}", index + 1, iterations.len());
+
+        Benchmarks {
+            benchmarks: criterion::run(
+                iteration,
+                &inputs,
+                cache,
+                events.as_ref(),
+            )?,
+            metadata: &metadata,
+            name: iteration.to_string(),
+        }
+        .write(&output_directory)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,617 @@
+//! Hardware and software metadata of the benchmarking system, gathered from
+//! `/proc`, `/sys`, and the Git and Rust toolchains.
+//!
+//! Unavailable hardware details, such as the RAM details only readable by root,
+//! are serialized as `null`.
+
+use itertools::Itertools;
+use serde::Serialize;
+use tap::Pipe;
+
+use std::{
+    collections::HashSet,
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+type Error = Box<dyn std::error::Error>;
+
+const BYTES_PER_SECTOR: u64 = 512;
+const COMPANY_SUFFIXES: [&str; 7] = [
+    "Co.",
+    "Corp.",
+    "Corporation",
+    "Inc.",
+    "Limited",
+    "Ltd",
+    "Ltd.",
+];
+const PCI_IDS: [&str; 3] = [
+    "/usr/share/hwdata/pci.ids",
+    "/usr/share/misc/pci.ids",
+    "/usr/share/pci.ids",
+];
+
+#[derive(Serialize)]
+pub struct Cpu {
+    pub cores: usize,
+    pub name: Option<String>,
+    pub threads: usize,
+}
+
+#[derive(Serialize)]
+pub struct Gpu {
+    pub name: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Hardware {
+    pub architecture: String,
+    pub cpu: Cpu,
+    pub gpu: Gpu,
+    pub ram: Ram,
+    pub storage: Storage,
+}
+
+#[derive(Serialize)]
+pub struct Kernel {
+    pub name: Option<String>,
+    pub version: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Metadata {
+    pub commit: String,
+    pub hardware: Hardware,
+    pub software: Software,
+}
+
+#[derive(Serialize)]
+pub struct Os {
+    pub name: Option<String>,
+    pub version: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Ram {
+    pub frequency: Option<String>,
+    pub name: Option<String>,
+    pub size: Option<String>,
+
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Rust {
+    pub cargo: String,
+    pub rustc: String,
+}
+
+#[derive(Serialize)]
+pub struct Software {
+    pub filesystem: Option<String>,
+    pub kernel: Kernel,
+    pub os: Os,
+    pub rust: Rust,
+}
+
+#[derive(Serialize)]
+pub struct Storage {
+    pub name: Option<String>,
+    pub size: Option<String>,
+}
+
+impl Metadata {
+    pub fn collect(pci_ids: Option<&Path>) -> Result<Self, Error> {
+        let (root_device, filesystem) = root_mount().unzip();
+
+        Ok(Self {
+            commit: command("git", &["rev-parse", "HEAD"])?,
+
+            hardware: Hardware {
+                architecture: env::consts::ARCH.to_string(),
+                cpu: cpu()?,
+                gpu: Gpu { name: gpu(pci_ids) },
+                ram: ram(),
+                storage: root_device.as_deref().map(storage).unwrap_or(
+                    Storage {
+                        name: None,
+                        size: None,
+                    },
+                ),
+            },
+
+            software: Software {
+                filesystem,
+
+                kernel: Kernel {
+                    name: read("/proc/sys/kernel/ostype"),
+                    version: read("/proc/sys/kernel/osrelease"),
+                },
+
+                os: os(),
+
+                rust: Rust {
+                    cargo: command("cargo", &["--version"])?,
+                    rustc: command("rustc", &["--version"])?,
+                },
+            },
+        })
+    }
+}
+
+/// Format `bytes` in binary units, like `32 GiB` or `1.82 TiB`.
+fn binary_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.2}")
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .pipe(|size| format!("{size} {}", UNITS[unit]))
+}
+
+fn command(program: &str, args: &[&str]) -> Result<String, Error> {
+    let output = Command::new(program).args(args).output()?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "{program} {} failed: {}",
+            args.join(" "),
+            output.status
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+fn cpu() -> io::Result<Cpu> {
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo")?;
+
+    let processors = cpuinfo
+        .split("\n\n")
+        .filter(|processor| !processor.trim().is_empty())
+        .map(|processor| {
+            processor
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let field = |processor: &[(&str, &str)], key: &str| {
+        processor
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value.to_string())
+    };
+
+    let threads = processors.len();
+
+    // Identify physical cores by their package and core IDs, falling back to
+    // one core per thread when the topology is not exposed.
+    let cores = processors
+        .iter()
+        .filter_map(|processor| {
+            field(processor, "physical id").zip(field(processor, "core id"))
+        })
+        .collect::<HashSet<_>>()
+        .len()
+        .pipe(|cores| if cores == 0 { threads } else { cores });
+
+    Ok(Cpu {
+        cores,
+        name: processors
+            .first()
+            .and_then(|processor| field(processor, "model name")),
+        threads,
+    })
+}
+
+/// Name the first display controller bound to a driver, using the PCI ID
+/// database when available.
+fn gpu(pci_ids: Option<&Path>) -> Option<String> {
+    let (vendor, device) = fs::read_dir("/sys/bus/pci/devices")
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .sorted()
+        .find(|device| {
+            read(device.join("class"))
+                .is_some_and(|class| class.starts_with("0x03"))
+                && device.join("driver").exists()
+        })?
+        .pipe(|device| {
+            let id = |file| {
+                read(device.join(file))
+                    .map(|id| id.trim_start_matches("0x").to_string())
+            };
+
+            id("vendor").zip(id("device"))
+        })?;
+
+    pci_ids
+        .map(Path::to_path_buf)
+        .into_iter()
+        .chain(PCI_IDS.iter().map(PathBuf::from))
+        .find_map(|path| fs::read_to_string(path).ok())
+        .and_then(|pci_ids| pci_name(&pci_ids, &vendor, &device))
+        .or_else(|| Some(format!("{vendor}:{device}")))
+}
+
+/// Name the `device` of `vendor` from the PCI ID database `pci_ids`.
+///
+/// Company suffixes and commas are removed from the vendor name like `inxi`
+/// does, matching the names in datasets gathered with it.
+fn pci_name(pci_ids: &str, vendor: &str, device: &str) -> Option<String> {
+    let mut lines = pci_ids
+        .lines()
+        .skip_while(|line| !line.starts_with(&format!("{vendor} ")));
+
+    let vendor = lines.next()?[vendor.len()..]
+        .replace(',', "")
+        .split_whitespace()
+        .filter(|word| !COMPANY_SUFFIXES.contains(word))
+        .join(" ");
+
+    lines
+        .take_while(|line| line.starts_with('\t') || line.starts_with('#'))
+        .find_map(|line| line.strip_prefix(&format!("\t{device} ")))
+        .map(|device| format!("{vendor} {}", device.trim()))
+}
+
+fn os() -> Os {
+    let os_release = read("/etc/os-release").unwrap_or_default();
+
+    let field = |key: &str| {
+        os_release
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value.trim_matches('"').to_string())
+    };
+
+    Os {
+        name: field("NAME"),
+        version: field("VERSION_ID"),
+    }
+}
+
+/// Describe the installed memory devices from their SMBIOS type 17
+/// structures, falling back to the memory size reported by the kernel.
+fn ram() -> Ram {
+    const MEMORY_DEVICE: &str = "17-";
+
+    let devices = fs::read_dir("/sys/firmware/dmi/entries")
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|entry| {
+            entry
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(MEMORY_DEVICE))
+        })
+        .sorted()
+        .filter_map(|entry| fs::read(entry.join("raw")).ok())
+        .filter_map(|raw| MemoryDevice::parse(&raw))
+        .filter(|device| device.size != 0)
+        .collect::<Vec<_>>();
+
+    let size = if devices.is_empty() {
+        read("/proc/meminfo").and_then(|meminfo| {
+            meminfo
+                .lines()
+                .find_map(|line| line.strip_prefix("MemTotal:"))
+                .and_then(|size| {
+                    size.trim()
+                        .trim_end_matches("kB")
+                        .trim()
+                        .parse::<u64>()
+                        .ok()
+                })
+                .map(|kibibytes| binary_size(kibibytes * 1024))
+        })
+    } else {
+        Some(binary_size(devices.iter().map(|device| device.size).sum()))
+    };
+
+    let device = devices.into_iter().next();
+
+    Ram {
+        frequency: device
+            .as_ref()
+            .and_then(|device| device.speed)
+            .map(|speed| format!("{speed} MT/s")),
+
+        name: device
+            .as_ref()
+            .and_then(|device| device.part_number.clone()),
+        size,
+        kind: device.and_then(|device| device.kind),
+    }
+}
+
+fn read(path: impl AsRef<Path>) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty())
+}
+
+/// Find the block device and filesystem mounted at `/`.
+fn root_mount() -> Option<(String, String)> {
+    read("/proc/self/mountinfo")?
+        .lines()
+        .rev()
+        .find_map(|line| {
+            let (mount, filesystem) = line.split_once(" - ")?;
+
+            if mount.split(' ').nth(4)? != "/" {
+                return None;
+            }
+
+            let mut filesystem = filesystem.split(' ');
+
+            let kind = filesystem.next()?.to_string();
+            let device = filesystem.next()?.strip_prefix("/dev/")?.to_string();
+
+            Some((device, kind))
+        })
+}
+
+fn storage(partition: &str) -> Storage {
+    let partition = Path::new("/sys/class/block").join(partition);
+
+    // Partitions are nested inside the directory of their disk.
+    let disk = partition.canonicalize().ok().map(|path| {
+        if path.join("partition").exists() {
+            path.parent().map(Path::to_path_buf).unwrap_or(path)
+        } else {
+            path
+        }
+    });
+
+    Storage {
+        name: disk.and_then(|disk| read(disk.join("device/model"))),
+
+        size: read(partition.join("size"))
+            .and_then(|sectors| sectors.parse::<u64>().ok())
+            .map(|sectors| binary_size(sectors * BYTES_PER_SECTOR)),
+    }
+}
+
+struct MemoryDevice {
+    kind: Option<String>,
+    part_number: Option<String>,
+    size: u64,
+    speed: Option<u16>,
+}
+
+impl MemoryDevice {
+    /// Parse a raw SMBIOS memory device (type 17) structure.
+    fn parse(raw: &[u8]) -> Option<Self> {
+        const CONFIGURED_SPEED: usize = 0x20;
+        const EXTENDED_SIZE: usize = 0x1C;
+        const KIND: usize = 0x12;
+        const MEBIBYTE: u64 = 1024 * 1024;
+        const PART_NUMBER: usize = 0x1A;
+        const SIZE: usize = 0x0C;
+        const SPEED: usize = 0x15;
+
+        let length = *raw.get(1)? as usize;
+        let formatted = raw.get(..length)?;
+
+        let byte = |offset: usize| formatted.get(offset).copied();
+
+        let word = |offset: usize| {
+            formatted
+                .get(offset..offset + 2)
+                .map(|word| u16::from_le_bytes([word[0], word[1]]))
+        };
+
+        let string = |index: u8| {
+            raw[length..]
+                .split(|&byte| byte == 0)
+                .nth(index.checked_sub(1)? as usize)
+                .and_then(|string| std::str::from_utf8(string).ok())
+                .map(str::trim)
+                .filter(|string| !string.is_empty())
+                .map(str::to_string)
+        };
+
+        let size = match word(SIZE)? {
+            0xFFFF => 0,
+            0x7FFF => {
+                formatted
+                    .get(EXTENDED_SIZE..EXTENDED_SIZE + 4)
+                    .map(|size| {
+                        u32::from_le_bytes([size[0], size[1], size[2], size[3]])
+                    })? as u64
+                    * MEBIBYTE
+            }
+
+            // The most significant bit selects kibibytes over mebibytes.
+            size if size & 0x8000 != 0 => (size & 0x7FFF) as u64 * 1024,
+            size => size as u64 * MEBIBYTE,
+        };
+
+        Some(Self {
+            kind: byte(KIND).and_then(|kind| {
+                match kind {
+                    0x12 => Some("DDR"),
+                    0x13 => Some("DDR2"),
+                    0x18 => Some("DDR3"),
+                    0x1A => Some("DDR4"),
+                    0x1B => Some("LPDDR"),
+                    0x1C => Some("LPDDR2"),
+                    0x1D => Some("LPDDR3"),
+                    0x1E => Some("LPDDR4"),
+                    0x22 => Some("DDR5"),
+                    0x23 => Some("LPDDR5"),
+                    _ => None,
+                }
+                .map(str::to_string)
+            }),
+
+            part_number: byte(PART_NUMBER).and_then(string),
+            size,
+
+            speed: word(CONFIGURED_SPEED)
+                .filter(|&speed| speed != 0)
+                .or_else(|| word(SPEED))
+                .filter(|&speed| speed != 0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATABASE: &str = "\
+# List of PCI ID's
+1002  Advanced Micro Devices, Inc. [AMD/ATI]
+\t1638  Cezanne [Radeon Vega Series / Radeon Vega Mobile Series]
+\t\t1043 16c2  Radeon Vega 8
+# Comment between devices
+\t164c  Lucienne
+8086  Intel Corporation
+\t9a49  TigerLake-LP GT2 [Iris Xe Graphics]
+10de  NVIDIA Corporation
+\t2684  AD102 [GeForce RTX 4090]
+";
+
+    /// SMBIOS type 17 structure of `length` bytes with `fields` set at their
+    /// offsets, followed by its strings.
+    fn memory_device(
+        length: u8,
+        fields: &[(usize, &[u8])],
+        strings: &[&str],
+    ) -> Vec<u8> {
+        let mut raw = vec![0; length as usize];
+        raw[0] = 17;
+        raw[1] = length;
+
+        for (offset, bytes) in fields {
+            raw[*offset..*offset + bytes.len()].copy_from_slice(bytes);
+        }
+
+        for string in strings {
+            raw.extend_from_slice(string.as_bytes());
+            raw.push(0);
+        }
+
+        raw.push(0);
+        raw
+    }
+
+    #[test]
+    fn binary_size_uses_largest_unit() {
+        assert_eq!(binary_size(512), "512 B");
+        assert_eq!(binary_size(32 << 30), "32 GiB");
+        assert_eq!(binary_size(2_000_398_934_016), "1.82 TiB");
+    }
+
+    #[test]
+    fn pci_name_matches_inxi() {
+        assert_eq!(
+            pci_name(DATABASE, "1002", "164c").as_deref(),
+            Some("Advanced Micro Devices [AMD/ATI] Lucienne")
+        );
+
+        assert_eq!(
+            pci_name(DATABASE, "8086", "9a49").as_deref(),
+            Some("Intel TigerLake-LP GT2 [Iris Xe Graphics]")
+        );
+
+        assert_eq!(
+            pci_name(DATABASE, "10de", "2684").as_deref(),
+            Some("NVIDIA AD102 [GeForce RTX 4090]")
+        );
+    }
+
+    #[test]
+    fn pci_name_ignores_other_vendors_and_subsystems() {
+        assert_eq!(pci_name(DATABASE, "1002", "16c2"), None);
+        assert_eq!(pci_name(DATABASE, "1002", "9a49"), None);
+        assert_eq!(pci_name(DATABASE, "1234", "164c"), None);
+    }
+
+    #[test]
+    fn memory_device_parses_ddr4() {
+        let raw = memory_device(
+            0x28,
+            &[
+                (0x0C, &16384_u16.to_le_bytes()),
+                (0x12, &[0x1A]),
+                (0x15, &3200_u16.to_le_bytes()),
+                (0x1A, &[2]),
+            ],
+            &["DIMM 0", "M471A2K43EB1-CWE  "],
+        );
+
+        let device =
+            MemoryDevice::parse(&raw).expect("memory device should parse");
+
+        assert_eq!(device.kind.as_deref(), Some("DDR4"));
+        assert_eq!(device.part_number.as_deref(), Some("M471A2K43EB1-CWE"));
+        assert_eq!(device.size, 16 << 30);
+        assert_eq!(device.speed, Some(3200));
+    }
+
+    #[test]
+    fn memory_device_prefers_configured_speed() {
+        let raw = memory_device(
+            0x28,
+            &[
+                (0x15, &4800_u16.to_le_bytes()),
+                (0x20, &4400_u16.to_le_bytes()),
+            ],
+            &[],
+        );
+
+        let device =
+            MemoryDevice::parse(&raw).expect("memory device should parse");
+
+        assert_eq!(device.speed, Some(4400));
+        assert_eq!(device.part_number, None);
+        assert_eq!(device.kind, None);
+    }
+
+    #[test]
+    fn memory_device_decodes_size_units() {
+        let size = |fields: &[(usize, &[u8])]| {
+            MemoryDevice::parse(&memory_device(0x28, fields, &[]))
+                .expect("memory device should parse")
+                .size
+        };
+
+        assert_eq!(size(&[(0x0C, &0x8200_u16.to_le_bytes())]), 512 << 10);
+        assert_eq!(size(&[(0x0C, &0xFFFF_u16.to_le_bytes())]), 0);
+
+        assert_eq!(
+            size(&[
+                (0x0C, &0x7FFF_u16.to_le_bytes()),
+                (0x1C, &65536_u32.to_le_bytes()),
+            ]),
+            64 << 30
+        );
+    }
+
+    #[test]
+    fn memory_device_rejects_truncated_structure() {
+        assert!(MemoryDevice::parse(&[17]).is_none());
+        assert!(MemoryDevice::parse(&[17, 0x28, 0, 0]).is_none());
+    }
+}
@@ -242,6 +242,7 @@
             packages = [
               inputs.self.checks.${system}.git-hooks.enabledPackages
               pkgs.cargo-criterion
+              pkgs.cargo-fuzz
             ];
           };
 
@@ -367,12 +368,23 @@
                           mainProgram = package;
                         };
 
-                        src = crane.workspace.src [path];
+                        # Path dependencies on other workspace crates are
+                        # built from source alongside.
+                        src = crane.workspace.src [./crates];
                       };
                   }
               )
               {}
-              (builtins.attrNames (builtins.readDir ./crates));
+              (
+                builtins.filter
+                (
+                  package:
+                    builtins.pathExists (
+                      lib.path.append ./crates "${package}/src/main.rs"
+                    )
+                )
+                (builtins.attrNames (builtins.readDir ./crates))
+              );
           in
             lib.fix (
               self:
@@ -386,67 +398,19 @@
                       name = "benchmark";
 
                       runtimeInputs = [
-                        (
-                          pkgs.inxi.override {
-                            withRecommendedSystemPrograms = true;
-                          }
-                        )
-
                         fenix
                         pkgs.auto-cpufreq
                         pkgs.cargo-criterion
                         pkgs.coreutils
                         pkgs.diffutils
-                        pkgs.findutils
-                        pkgs.gawk
                         pkgs.gcc
                         pkgs.gitMinimal
                         pkgs.gnused
-                        pkgs.jq
                         pkgs.systemdMinimal
+                        self.runner
                       ];
 
-                      text = let
-                        inputs'' =
-                          lib.concatMapStringsSep
-                          "\n    "
-                          (
-                            input: "[${
-                              lib.removePrefix "input-" input.name
-                            }]=${input}/${
-                              let
-                                files = builtins.attrNames (
-                                  builtins.readDir input
-                                );
-
-                                length = builtins.length files;
-                              in
-                                lib.throwIfNot
-                                (length == 1)
-                                "expected one file inside ${input}, but got: ${length}"
-                                (builtins.head files)
-                            }"
-                          )
-                          (
-                            builtins.sort
-                            (
-                              a: b:
-                                builtins.head (lib.naturalSort [a.name b.name])
-                                == a.name
-                            )
-                            (builtins.attrValues inputs')
-                          );
-
-                        iterations = builtins.concatStringsSep "\n    " (
-                          lib.naturalSort (
-                            map (lib.removeSuffix ".rs") (
-                              builtins.attrNames (
-                                builtins.readDir crates/iterations/src/iterations
-                              )
-                            )
-                          )
-                        );
-                      in ''
+                      text = ''
                         check_current_working_directory() {
                           git rev-parse --is-inside-work-tree >/dev/null 2>&1 ||
                             return
@@ -456,283 +420,6 @@
                           done
                         }
 
-                        filter_benchmarks() {
-                          printf 'iteration filter: %s\n' "$iteration_filter"
-                          printf 'input filter: %s\n' "$input_filter"
-
-                          for input in "''${!inputs[@]}"; do
-                            if ! [[ "$input" =~ $input_filter ]]; then
-                              unset 'inputs[$input]'
-                            fi
-                          done
-
-                          for iteration in "''${!iterations[@]}"; do
-                            if
-                              ! [[
-                                "''${iterations[$iteration]}" =~ $iteration_filter
-                              ]];
-                            then
-                              unset 'iterations[$iteration]'
-                            fi
-                          done
-
-                          printf \
-                            'selected %s: %s\n' \
-                            iterations \
-                            "$(
-                              printf '%s, ' "''${iterations[@]}" | sed 's/, $//'
-                            )" \
-                            inputs \
-                            "$(
-                              printf '%s\n' "''${!inputs[@]}" |
-                                sort --general-numeric-sort |
-                                xargs printf '%s, ' |
-                                sed 's/, $//'
-                            )"
-                        }
-
-                        get_metadata() {
-                          local inxi_json_cache
-
-                          inxi_json_cache="$(
-                            inxi \
-                              --admin \
-                              --cpu \
-                              --disk \
-                              --graphics \
-                              --memory \
-                              --output json \
-                              --output-file print \
-                              --partitions \
-                              --system |
-                              jq '
-                                walk(
-                                  if type == "object" then
-                                    with_entries(
-                                      .key |= (
-                                        capture(
-                                          "^[0-9]+#[0-9]+#[0-9]+#(?<key>.*)"
-                                        ).key // .
-                                      )
-                                    )
-
-                                  else
-                                    .
-                                  end
-                                ) |
-                                add
-                              '
-                          )"
-
-                          inxi_access_first() {
-                            inxi_select_first "$1" ".\"$2\" != null" "$2"
-                          }
-
-                          inxi_select_first() {
-                            inxi_query "[.\"$1\"[] | select($2)][0] | .\"$3\""
-                          }
-
-                          inxi_query() {
-                            jq --raw-output "$1" <<<"$inxi_json_cache"
-                          }
-
-                          commit="$(git rev-parse HEAD)"
-
-                          software_rust_cargo_version="$(cargo --version)"
-                          software_rust_rustc_version="$(rustc --version)"
-
-                          hardware_architecture="$(inxi_access_first System arch)"
-
-                          hardware_cpu_cores="$(inxi_access_first CPU cores)"
-                          hardware_cpu_name="$(inxi_access_first CPU model)"
-                          hardware_cpu_threads="$(inxi_access_first CPU threads)"
-
-                          hardware_gpu_name="$(
-                            inxi_select_first \
-                              Graphics \
-                              "
-                                .Device != null and
-                                .driver != null and
-                                .type != \"USB\"
-                              " \
-                              Device
-                          )"
-
-                          hardware_ram_name="$(inxi_access_first Memory part-no)"
-                          hardware_ram_size="$(inxi_access_first Memory total)"
-                          hardware_ram_speed="$(inxi_access_first Memory speed)"
-                          hardware_ram_type="$(inxi_access_first Memory type)"
-
-                          hardware_storage_name="$(
-                            inxi_access_first Drives model
-                          )"
-
-                          hardware_storage_size="$(
-                            inxi_select_first Partition ".ID == \"/\"" raw-size
-                          )"
-
-                          hardware_storage_speed="$(
-                            inxi_access_first Drives speed
-                          )"
-
-                          software_filesystem="$(
-                            inxi_select_first Partition ".ID == \"/\"" fs
-                          )"
-
-                          software_kernel_name="$(uname --kernel-name)"
-
-                          software_kernel_version="$(
-                            inxi_access_first System Kernel
-                          )"
-
-                          software_os_name="$(
-                            inxi_access_first System Distro | awk '{ print $1 }'
-                          )"
-
-                          software_os_version="$(
-                            inxi_access_first System Distro | awk '{ print $2 }'
-                          )"
-
-                          unset -f inxi_access_first inxi_query inxi_select_first
-                        }
-
-                        run_benchmarks() {
-                          local total_inputs=''${#inputs[@]}
-                          local total_iterations=''${#iterations[@]}
-
-                          mkdir --parents benchmarks
-
-                          for iteration in "''${!iterations[@]}"; do
-                            local input_index=1
-
-                            for input in $(
-                              printf '%s\n' "''${!inputs[@]}" |
-                                sort --general-numeric-sort
-                            ); do
-                              printf \
-                                '[%d/%d][%d/%d] %s %s\n' \
-                                "$((iteration + 1))" \
-                                "$total_iterations" \
-                                "$input_index" \
-                                "$total_inputs" \
-                                "''${iterations[$iteration]}" \
-                                "''${inputs[$input]}" \
-                                >&2
-
-                              INPUT="''${inputs[$input]}" \
-                                cargo criterion \
-                                  --message-format json \
-                                  -- \
-                                  "''${iterations[$iteration]}" |
-                                  jq "
-                                    select(.reason == \"benchmark-complete\") |
-                                    {
-                                      input: $input,
-                                      time: .mean.estimate
-                                    }
-                                  "
-
-                              ((++input_index))
-                            done |
-                              jq \
-                                --slurp \
-                                --arg iteration_name "''${iterations[$iteration]}" \
-                                --arg metadata_commit "$commit" \
-                                --arg metadata_hardware_architecture "$hardware_architecture" \
-                                --arg metadata_hardware_cpu_cores "$hardware_cpu_cores" \
-                                --arg metadata_hardware_cpu_name "$hardware_cpu_name" \
-                                --arg metadata_hardware_cpu_threads "$hardware_cpu_threads" \
-                                --arg metadata_hardware_gpu_name "$hardware_gpu_name" \
-                                --arg metadata_hardware_ram_name "$hardware_ram_name" \
-                                --arg metadata_hardware_ram_size "$hardware_ram_size" \
-                                --arg metadata_hardware_ram_speed "$hardware_ram_speed" \
-                                --arg metadata_hardware_ram_type "$hardware_ram_type" \
-                                --arg metadata_hardware_storage_name "$hardware_storage_name" \
-                                --arg metadata_hardware_storage_size "$hardware_storage_size" \
-                                --arg metadata_hardware_storage_speed "$hardware_storage_speed" \
-                                --arg metadata_software_filesystem "$software_filesystem" \
-                                --arg metadata_software_kernel_name "$software_kernel_name" \
-                                --arg metadata_software_kernel_version "$software_kernel_version" \
-                                --arg metadata_software_os_name "$software_os_name" \
-                                --arg metadata_software_os_version "$software_os_version" \
-                                --arg metadata_software_rust_cargo_version "$software_rust_cargo_version" \
-                                --arg metadata_software_rust_rustc_version "$software_rust_rustc_version" \
-                                '
-                                  {
-                                    benchmarks: .,
-
-                                    metadata: {
-                                      commit: $metadata_commit,
-
-                                      hardware: {
-                                        architecture:
-                                          $metadata_hardware_architecture,
-
-                                        cpu: {
-                                          cores: (
-                                            $metadata_hardware_cpu_cores |
-                                              tonumber
-                                          ),
-
-                                          name: $metadata_hardware_cpu_name,
-
-                                          threads: (
-                                            $metadata_hardware_cpu_threads |
-                                              tonumber
-                                          )
-                                        },
-
-                                        gpu: {
-                                          name: $metadata_hardware_gpu_name
-                                        },
-
-                                        ram: {
-                                          frequency:
-                                            $metadata_hardware_ram_speed,
-
-                                          name: $metadata_hardware_ram_name,
-                                          size: $metadata_hardware_ram_size,
-                                          type: $metadata_hardware_ram_type
-                                        },
-
-                                        storage: {
-                                          name: $metadata_hardware_storage_name,
-                                          size: $metadata_hardware_storage_size
-                                        }
-                                      },
-
-                                      software: {
-                                        filesystem: $metadata_software_filesystem,
-
-                                        kernel: {
-                                          name: $metadata_software_kernel_name,
-
-                                          version:
-                                            $metadata_software_kernel_version
-                                        },
-
-                                        os: {
-                                          name: $metadata_software_os_name,
-                                          version: $metadata_software_os_version
-                                        },
-
-                                        rust: {
-                                          cargo:
-                                            $metadata_software_rust_cargo_version,
-
-                                          rustc:
-                                            $metadata_software_rust_rustc_version
-                                        }
-                                      }
-                                    },
-
-                                    name: $iteration_name
-                                  }
-                                ' \
-                                >"benchmarks/''${iterations[$iteration]}.json"
-                          done
-                        }
-
                         stabilize_system() {
                           local -r target_governor=performance
 
@@ -758,9 +445,6 @@
                         }
 
                         main() {
-                          local input_filter="^(''${2:-.*})$"
-                          local iteration_filter="''${1:-.*}"
-
                           if ! check_current_working_directory; then
                             printf \
                               'Current directory (%s) does not match expected Nix source tree: %s\n' \
@@ -771,18 +455,14 @@
                             exit 1
                           fi
 
-                          declare -A inputs=(
-                            ${inputs''}
-                          )
-
-                          local iterations=(
-                            ${iterations}
-                          )
-
-                          filter_benchmarks
                           stabilize_system || :
-                          get_metadata
-                          run_benchmarks
+
+                          runner \
+                            --input-filter "''${2:-.*}" \
+                            --iteration-filter "''${1:-.*}" \
+                            --pci-ids ${pkgs.hwdata}/share/hwdata/pci.ids \
+                            -- \
+                            ${lib.escapeShellArgs (builtins.attrValues inputs')}
                         }
 
                         main "$@"
@@ -989,51 +669,42 @@
                       paths = builtins.attrValues inputs';
                     };
 
-                    plots =
-                      lib.recursiveUpdate
-                      (
-                        pkgs.linkFarmFromDrvs "plots" (
-                          map
-                          (
-                            benchmarks: let
-                              benchmarks' = lib.escapeShellArgs (
-                                map
-                                (benchmark: "${./benchmarks}/${benchmark}")
-                                benchmarks
-                              );
-                            in
-                              pkgs.runCommand
-                              (
-                                lib.concatMapStringsSep
-                                "-"
-                                (lib.removeSuffix ".json")
-                                benchmarks
-                              )
-                              {nativeBuildInputs = [self.plot];}
-                              ''
-                                mkdir --parents $out
-                                plot --output-directory $out -- ${benchmarks'}
-                              ''
-                          )
-                          (
-                            let
-                              benchmark = builtins.elemAt benchmarks;
+                    plots = let
+                      benchmarks = map (benchmark: "${./benchmarks}/${benchmark}") (
+                        builtins.attrNames (builtins.readDir ./benchmarks)
+                      );
 
-                              benchmarks = builtins.attrNames (
-                                builtins.readDir ./benchmarks
-                              );
-                            in
-                              [[(builtins.head benchmarks)]]
-                              ++ builtins.concatMap
-                              (index: [[(benchmark index) (benchmark (index + 1))]])
-                              (lib.range 0 (builtins.length benchmarks - 2))
-                              ++ [benchmarks]
-                          )
+                      baseline = lib.escapeShellArg (
+                        lib.removeSuffix ".json" (
+                          builtins.baseNameOf (builtins.head benchmarks)
                         )
-                      )
+                      );
+
+                      benchmarks' = lib.escapeShellArgs benchmarks;
+                    in
+                      pkgs.runCommand "plots"
                       {
-                        meta.description = "Consecutive benchmark plots, with an overall plot combining all benchmarks";
-                      };
+                        meta.description = "Benchmark plots combining all benchmarks, alongside grids of consecutive benchmark pairs";
+                        nativeBuildInputs = [self.plot];
+                      }
+                      ''
+                        mkdir --parents $out
+
+                        plot \
+                          --baseline ${baseline} \
+                          --output-directory $out \
+                          -- \
+                          ${benchmarks'}
+
+                        plot \
+                          --baseline ${baseline} \
+                          --filename-template '{name}-pairs.{extension}' \
+                          --grid pair \
+                          --output-directory $out \
+                          --plots time,throughput-rows,throughput-bytes,speedup,fit \
+                          -- \
+                          ${benchmarks'}
+                      '';
 
                     workspace = pkgs.buildEnv {
                       meta.description = "Rust implementation of the performance-oriented One Billion Row Challenge (1BRC)";